@@ -0,0 +1,231 @@
+use anyhow::Result;
+use boots_core::config::{OPTIONS, OptionSpec};
+use boots_core::generator::Manifest;
+use boots_core::{Module, ProjectType, TemplatePack, TemplateSource};
+use serde_json::{Value, json};
+
+/// What `boots list` prints: aligned rows, or JSON with `--json`
+pub struct Listing {
+    /// Lines printed above the rows
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    json: Value,
+}
+
+impl Listing {
+    pub fn print(&self, json: bool) -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&self.json)?);
+            return Ok(());
+        }
+
+        for line in &self.header {
+            println!("{}", line);
+        }
+        let columns = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .filter_map(|r| r.get(i))
+                    .map(String::len)
+                    .max()
+            })
+            .map(|width| width.unwrap_or(0) + 2)
+            .collect();
+        for row in &self.rows {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                if i + 1 == row.len() {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{:<width$}", cell, width = widths[i]));
+                }
+            }
+            println!("{}", line.trim_end());
+        }
+        Ok(())
+    }
+}
+
+pub fn types() -> Listing {
+    let rows = ProjectType::ALL
+        .iter()
+        .map(|t| vec![t.to_string(), t.description().to_string()])
+        .collect();
+    let json = ProjectType::ALL
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.as_str(),
+                "description": t.description(),
+                "modules": names(t.required_modules()),
+                "optional_modules": names(t.optional_modules()),
+                "options": OPTIONS
+                    .iter()
+                    .filter(|spec| spec.applies_to(*t))
+                    .map(|spec| spec.name)
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    Listing {
+        header: Vec::new(),
+        rows,
+        json,
+    }
+}
+
+/// Every option, or those that apply to `project_type`
+pub fn options(project_type: Option<ProjectType>) -> Listing {
+    let options: Vec<&OptionSpec> = OPTIONS
+        .iter()
+        .filter(|spec| project_type.is_none_or(|t| spec.applies_to(t)))
+        .collect();
+    let rows = options
+        .iter()
+        .map(|spec| {
+            let mut description = spec.description.to_string();
+            if !spec.aliases.is_empty() {
+                description.push_str(&format!(", also {}", spec.aliases.join(", ")));
+            }
+            if spec.negatable {
+                description.push_str(&format!(", no-{} turns it off", spec.name));
+            }
+            vec![spec.name.to_string(), types_of(spec), description]
+        })
+        .collect();
+    let json = options
+        .iter()
+        .map(|spec| {
+            json!({
+                "name": spec.name,
+                "aliases": spec.aliases,
+                "group": spec.kind.group(),
+                "description": spec.description,
+                "project_types": spec.project_types.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+                "negatable": spec.negatable,
+            })
+        })
+        .collect();
+    Listing {
+        header: Vec::new(),
+        rows,
+        json,
+    }
+}
+
+pub fn modules() -> Listing {
+    let availability = |module: Module| {
+        ProjectType::ALL.iter().filter_map(move |t| {
+            if t.required_modules().contains(&module) {
+                Some((*t, true))
+            } else if t.optional_modules().contains(&module) {
+                Some((*t, false))
+            } else {
+                None
+            }
+        })
+    };
+    let rows = Module::ALL
+        .iter()
+        .map(|m| {
+            let types: Vec<String> = availability(*m)
+                .map(|(t, required)| match required {
+                    true => t.to_string(),
+                    false => format!("{} (optional)", t),
+                })
+                .collect();
+            vec![m.to_string(), types.join(", "), m.description().to_string()]
+        })
+        .collect();
+    let json = Module::ALL
+        .iter()
+        .map(|m| {
+            let (required, optional): (Vec<_>, Vec<_>) =
+                availability(*m).partition(|(_, required)| *required);
+            json!({
+                "name": m.as_str(),
+                "description": m.description(),
+                "required": required.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>(),
+                "optional": optional.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    Listing {
+        header: Vec::new(),
+        rows,
+        json,
+    }
+}
+
+/// Files of `templates/manifest.toml`, merged with those of packs and `--template-dir`,
+/// below the packs in use and the modules they provide
+pub fn templates(templates: &TemplateSource) -> Result<Listing> {
+    let manifest = Manifest::load(templates)?;
+    let packs = templates.packs();
+    let modules = |pack: &TemplatePack| match pack.manifest.modules.is_empty() {
+        true => "any".to_string(),
+        false => names(pack.manifest.modules.clone()).join(", "),
+    };
+    let header = packs
+        .iter()
+        .map(|pack| {
+            format!(
+                "# pack {} {} from {}, modules: {}",
+                pack.manifest.name,
+                pack.manifest.version,
+                pack.location.display(),
+                modules(pack)
+            )
+        })
+        .collect();
+    let rows = manifest
+        .files
+        .iter()
+        .map(|file| {
+            vec![
+                file.dest.clone(),
+                file.template
+                    .clone()
+                    .unwrap_or_else(|| "(empty)".to_string()),
+                file.when.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    let files: Vec<Value> = manifest
+        .files
+        .iter()
+        .map(|file| {
+            json!({
+                "dest": file.dest,
+                "template": file.template,
+                "render": file.render,
+                "when": file.when,
+            })
+        })
+        .collect();
+    let packs: Vec<Value> = packs
+        .iter()
+        .map(|pack| {
+            json!({
+                "name": pack.manifest.name,
+                "version": pack.manifest.version,
+                "path": pack.location,
+                "ref": pack.git_ref,
+                "modules": names(pack.manifest.modules.clone()),
+            })
+        })
+        .collect();
+    let json = json!({ "packs": packs, "files": files });
+    Ok(Listing { header, rows, json })
+}
+
+fn names(modules: Vec<Module>) -> Vec<&'static str> {
+    modules.iter().map(|m| m.as_str()).collect()
+}
+
+fn types_of(spec: &OptionSpec) -> String {
+    let types: Vec<&str> = spec.project_types.iter().map(|t| t.as_str()).collect();
+    types.join(", ")
+}
@@ -1,7 +1,25 @@
-use anyhow::Result;
-use boots_core::{ProjectGenerator, ProjectType, parse_options};
-use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use anyhow::{Context, Result};
+use boots_core::config::user::normalize_repository;
+use boots_core::config::{complete_options, options_for, parse_options_with};
+use boots_core::generator::{ConflictPolicy, DiffStatus, Hook, generate_with};
+use boots_core::{
+    GenerationPlan, Module, ProjectConfig, ProjectGenerator, ProjectType, TemplatePack,
+    TemplateSource, Upgrade, UpgradeStatus, UserConfig, Workspace, load_config,
+};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::CompleteEnv;
+use clap_complete::engine::{ArgValueCompleter, CompletionCandidate};
+use clap_complete::env::Shells;
 use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process;
+use wizard::Wizard;
+
+mod list;
+mod wizard;
 
 const ABOUT: &str = "Bootstrap modular Rust projects";
 const LONG_ABOUT: &str = "A CLI tool for bootstrapping modular Rust project structures.\n\n\
@@ -12,16 +30,135 @@ fn examples(prefix: &str) -> String {
         "Examples:\n  \
         {prefix} service my-api --options postgres,grpc\n  \
         {prefix} cli my-tool --options client\n  \
-        {prefix} lib my-crate"
+        {prefix} lib my-crate\n  \
+        {prefix} new\n  \
+        {prefix} new --config project.toml\n  \
+        {prefix} add persistence --options sqlite\n  \
+        {prefix} diff\n  \
+        {prefix} config set owner acme\n  \
+        {prefix} list options --json\n  \
+        {prefix} completions bash\n  \
+        {prefix} upgrade --dry-run"
     )
 }
 
+/// Short and long `--options` help for `project_type`, from the option table
+fn options_help(project_type: ProjectType) -> (String, String) {
+    let options = options_for(project_type);
+    let names: Vec<&str> = options.iter().map(|spec| spec.name).collect();
+    let help = format!("Additional features [possible: {}]", names.join(", "));
+
+    let mut long_help = "Comma-separated additional features:".to_string();
+    for spec in &options {
+        long_help.push_str(&format!("\n  {:<13}{}", spec.name, spec.description));
+    }
+    let negatable: Vec<&str> = options
+        .iter()
+        .filter(|spec| spec.negatable)
+        .map(|spec| spec.name)
+        .collect();
+    if !negatable.is_empty() {
+        long_help.push_str(&format!(
+            "\n\nno-<option> turns off: {}",
+            negatable.join(", ")
+        ));
+    }
+    (help, long_help)
+}
+
 #[derive(Parser)]
 #[command(name = "boots", bin_name = "boots")]
 #[command(author, version, about = ABOUT, long_about = LONG_ABOUT)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Template pack (directory, .tar.gz or git checkout) with a pack.toml manifest
+    #[arg(long, value_name = "PATH", global = true)]
+    template_pack: Option<PathBuf>,
+
+    /// Git ref to read the template pack from (requires --template-pack)
+    #[arg(long, value_name = "REF", global = true, requires = "template_pack")]
+    pack_ref: Option<String>,
+
+    /// Directory whose files override the built-in templates (e.g. docker/Dockerfile)
+    #[arg(long, value_name = "PATH", global = true)]
+    template_dir: Option<PathBuf>,
+
+    /// Print the files that would be created without writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+}
+
+/// Options shared by the commands that create a project
+#[derive(Args, Clone)]
+struct GenerateArgs {
+    /// Generate into DIR, which may exist and hold files (e.g. a fresh clone), instead of ./NAME
+    #[arg(long, visible_alias = "into", value_name = "DIR")]
+    path: Option<PathBuf>,
+
+    /// What to do with files that already exist in DIR: skip, overwrite, abort
+    #[arg(long, value_name = "POLICY", default_value_t, requires = "path")]
+    on_conflict: ConflictPolicy,
+
+    /// Owner of the repository on the git host: https://github.com/OWNER/NAME
+    #[arg(long, value_name = "OWNER")]
+    repo_owner: Option<String>,
+
+    /// Repository URL, e.g. https://gitlab.com/acme/my-api
+    #[arg(long, value_name = "URL", conflicts_with = "repo_owner")]
+    repository: Option<String>,
+
+    /// Run `git init` in the new project
+    #[arg(long)]
+    git: bool,
+
+    /// Run `cargo generate-lockfile`
+    #[arg(long)]
+    lock: bool,
+
+    /// Run `cargo fmt --all`
+    #[arg(long)]
+    fmt: bool,
+
+    /// Commit the generated files (implies --git)
+    #[arg(long)]
+    commit: bool,
+
+    /// Run no hooks, including those declared by template packs
+    #[arg(long, conflicts_with_all = ["git", "lock", "fmt", "commit"])]
+    no_hooks: bool,
+}
+
+impl GenerateArgs {
+    /// Apply the repository flags, which win over config files and user defaults
+    fn apply(&self, config: &mut ProjectConfig, defaults: &UserConfig) {
+        if let Some(url) = &self.repository {
+            config.repository = normalize_repository(url);
+        } else if let Some(owner) = &self.repo_owner {
+            let defaults = UserConfig {
+                owner: Some(owner.clone()),
+                ..defaults.clone()
+            };
+            config.repository = defaults.repository_for(&config.name);
+        }
+    }
+
+    /// Hooks picked with flags, or else the user's default hooks
+    fn hooks(&self, defaults: &UserConfig) -> Vec<Hook> {
+        if !(self.git || self.lock || self.fmt || self.commit) {
+            return defaults.hooks();
+        }
+        [
+            (self.git, Hook::Git),
+            (self.lock, Hook::Lock),
+            (self.fmt, Hook::Fmt),
+            (self.commit, Hook::Commit),
+        ]
+        .into_iter()
+        .filter_map(|(wanted, hook)| wanted.then_some(hook))
+        .collect()
+    }
 }
 
 #[derive(Subcommand, Clone)]
@@ -40,10 +177,12 @@ enum Commands {
         #[arg(value_name = "NAME")]
         name: String,
 
-        /// Comma-separated options: postgres, sqlite, grpc, http
+        /// Comma-separated additional features
         #[arg(short, long, value_name = "OPTIONS")]
-        #[arg(help = "Additional features [possible: postgres, sqlite, grpc, http]")]
         options: Option<String>,
+
+        #[command(flatten)]
+        args: GenerateArgs,
     },
 
     /// Create a CLI application project
@@ -58,10 +197,12 @@ enum Commands {
         #[arg(value_name = "NAME")]
         name: String,
 
-        /// Comma-separated options: client, persistence
+        /// Comma-separated additional features
         #[arg(short, long, value_name = "OPTIONS")]
-        #[arg(help = "Additional features [possible: client, persistence]")]
         options: Option<String>,
+
+        #[command(flatten)]
+        args: GenerateArgs,
     },
 
     /// Create a library crate project
@@ -73,6 +214,9 @@ enum Commands {
         /// Library name (e.g., my-lib, utils)
         #[arg(value_name = "NAME")]
         name: String,
+
+        #[command(flatten)]
+        args: GenerateArgs,
     },
 
     /// Create a sample board application with RBAC
@@ -90,14 +234,270 @@ enum Commands {
         #[arg(value_name = "NAME")]
         name: String,
 
-        /// Options (use 'sample' to create full board project)
+        /// Comma-separated additional features
         #[arg(short, long, value_name = "OPTIONS")]
-        #[arg(help = "Use 'sample' to create full board project (ignores other options)")]
         options: Option<String>,
+
+        #[command(flatten)]
+        args: GenerateArgs,
+    },
+
+    /// Create a project described by a TOML, JSON or YAML file, or interactively
+    #[command(
+        long_about = "Creates a project from a file holding the settings that the other \
+        commands take as NAME and --options, so service specs can be kept in a repository \
+        and regenerated reproducibly. The format follows the extension: .toml, .json, \
+        .yaml or .yml.\n\n\
+        Without --config, asks for the settings in the terminal, shows a summary with the \
+        equivalent one-line command and generates after confirmation.\n\n\
+        Example project.toml:\n  \
+        name = \"my-api\"\n  \
+        type = \"service\"          # service, cli, lib, sample\n  \
+        persistence = \"postgres\"  # postgres, sqlite, file\n  \
+        frontend = \"spa\"          # spa, ssr\n  \
+        grpc = true\n  \
+        http = true\n  \
+        client = false\n  \
+        author_name = \"Jane Doe\"  # defaults to git config\n  \
+        author_email = \"jane@example.com\"\n  \
+        license = \"MIT\""
+    )]
+    New {
+        /// Project description file; prompts for the settings when omitted
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        #[command(flatten)]
+        args: GenerateArgs,
+    },
+
+    /// Add a module to the project in the current directory
+    #[command(
+        long_about = "Generates crates/<module> in an existing project, adds it to \
+        the workspace members and wires it as a path dependency of the crate that uses it.\n\n\
+        Modules that can be added:\n  \
+        - client: HTTP client (cli projects)\n  \
+        - persistence: Database access layer (service and cli projects)"
+    )]
+    Add {
+        /// Module to add: client, persistence
+        #[arg(value_name = "MODULE")]
+        module: Module,
+
+        /// Comma-separated options for the module, e.g. postgres, sqlite
+        #[arg(short, long, value_name = "OPTIONS")]
+        options: Option<String>,
+    },
+
+    /// Remove an optional module from the project in the current directory
+    #[command(
+        long_about = "Deletes crates/<module>, drops it from the workspace members and \
+        from the dependencies of the other crates, then lists Rust source lines that still \
+        use it. Modules required by the project type cannot be removed."
+    )]
+    Remove {
+        /// Module to remove: client, persistence
+        #[arg(value_name = "MODULE")]
+        module: Module,
     },
+
+    /// Show how the project in the current directory differs from a fresh generation
+    #[command(
+        long_about = "Regenerates the project in memory and prints a unified diff from the \
+        generated files to the ones in the working tree. Files deleted from the project and \
+        files boots would now add are flagged. Files the project added itself are ignored.\n\n\
+        The settings come from .boots.toml, or are inferred from the workspace; \
+        --type and --options override them."
+    )]
+    Diff {
+        /// Project type to generate: service, cli, lib, sample
+        #[arg(long = "type", value_name = "TYPE")]
+        project_type: Option<ProjectType>,
+
+        /// Comma-separated options to generate with, e.g. postgres,grpc
+        #[arg(short, long, value_name = "OPTIONS")]
+        options: Option<String>,
+    },
+
+    /// Show or change the defaults in ~/.config/boots/config.toml
+    #[command(
+        long_about = "Manages the defaults applied to every project you generate. Each setting \
+        comes from the first of: the command line, a --config project file, \
+        ~/.config/boots/config.toml ($XDG_CONFIG_HOME/boots/config.toml when set), a BOOTS_<KEY> \
+        environment variable such as BOOTS_AUTHOR, and git config for the author.\n\n\
+        Keys:\n  \
+        - author: \"Name <email>\"\n  \
+        - owner: user or organization, giving the repository URL <repo_host>/<owner>/<name>\n  \
+        - repo_host: git host base URL, e.g. https://gitlab.com (https://github.com)\n  \
+        - license: SPDX license of generated projects (MIT)\n  \
+        - persistence: backend for a bare `persistence` option (postgres, sqlite, file)\n  \
+        - frontend: frontend for a bare `frontend` option (spa, ssr)\n  \
+        - template_pack: template pack used when --template-pack is not given\n  \
+        - hooks: comma-separated hooks run when no hook flag is given (git, lock, fmt, commit)"
+    )]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Merge template changes from this boots version into the project in the current directory
+    #[command(
+        long_about = "Regenerates the project with the settings recorded in .boots.toml, \
+        once with the boots version that generated it and once with this one, and applies \
+        the difference to the project files. Files you have not touched are replaced, edited \
+        files are merged line by line and overlapping edits are left as conflict markers.\n\n\
+        The original output comes from the boots executable given with --from, or from \
+        `cargo install boots --version <recorded version>` when omitted."
+    )]
+    Upgrade {
+        /// boots executable of the version the project was generated with
+        #[arg(long, value_name = "PATH")]
+        from: Option<PathBuf>,
+    },
+
+    /// List the project types, options, modules and template files boots supports
+    List {
+        #[command(subcommand)]
+        what: ListAction,
+
+        /// Print JSON instead of aligned text
+        #[arg(long, global = true)]
+        json: bool,
+    },
+
+    /// Print a shell script that completes boots commands, options and --options values
+    #[command(
+        long_about = "Prints a script that registers completions for the boots \
+        command, including the --options values each project type takes. Load it from your \
+        shell's startup file so it always matches the installed boots:\n\n  \
+        bash:        source <(boots completions bash)\n  \
+        zsh:         source <(boots completions zsh)\n  \
+        fish:        boots completions fish | source\n  \
+        elvish:      eval (boots completions elvish | slurp)\n  \
+        powershell:  boots completions powershell | Out-String | Invoke-Expression\n\n\
+        Run as `cargo boots completions`, the script completes `cargo boots ...` instead. It \
+        is registered for the whole cargo command and only knows its boots subcommand, so it \
+        replaces cargo's own completions in that shell: `cargo b<TAB>` then offers nothing \
+        but boots."
+    )]
+    Completions {
+        #[arg(value_name = "SHELL", value_parser = ["bash", "zsh", "fish", "powershell", "elvish"])]
+        shell: String,
+    },
+
+    /// Print the boots man page, or write a page per command into a directory
+    Man {
+        /// Write a page for the command and each subcommand into DIR
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+impl Commands {
+    /// Works on an existing project rather than generating a new one
+    fn is_project_command(&self) -> bool {
+        matches!(
+            self,
+            Commands::Add { .. }
+                | Commands::Remove { .. }
+                | Commands::Diff { .. }
+                | Commands::Upgrade { .. }
+        )
+    }
+}
+
+#[derive(Subcommand, Clone)]
+enum ListAction {
+    /// Project types with their modules and options
+    Types,
+    /// Values accepted by --options, and the project types they apply to
+    Options {
+        /// Only the options of this project type
+        #[arg(value_name = "TYPE")]
+        project_type: Option<ProjectType>,
+    },
+    /// Crates a project can contain
+    Modules,
+    /// Files written into generated projects, including those of --template-pack and --template-dir
+    Templates,
+}
+
+#[derive(Subcommand, Clone)]
+enum ConfigAction {
+    /// Print the effective value of KEY
+    Get {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+    /// Write KEY to the user config file; an empty VALUE removes it
+    Set {
+        #[arg(value_name = "KEY")]
+        key: String,
+        #[arg(value_name = "VALUE")]
+        value: String,
+    },
+    /// Print every key with its effective value and where it comes from
+    List,
+}
+
+/// The clap command with dynamic bin_name, examples and `--options` help and completions
+fn command(bin_name: &str) -> clap::Command {
+    let mut cmd = Cli::command()
+        .bin_name(bin_name)
+        .display_name(bin_name.replace(' ', "-"))
+        .after_help(examples(bin_name));
+    for project_type in [ProjectType::Service, ProjectType::Cli, ProjectType::Sample] {
+        let (help, long_help) = options_help(project_type);
+        let completer = ArgValueCompleter::new(move |current: &OsStr| {
+            let current = current.to_string_lossy();
+            complete_options(project_type, &current)
+                .into_iter()
+                .map(|(value, help)| CompletionCandidate::new(value).help(Some(help.into())))
+                .collect::<Vec<_>>()
+        });
+        cmd = cmd.mut_subcommand(project_type.as_str(), |sub| {
+            sub.mut_arg("options", |arg| {
+                arg.help(help).long_help(long_help).add(completer)
+            })
+        });
+    }
+    // Pages are named after the display name: boots.1, or cargo-boots.1 under cargo
+    let page = format!("{}.1", bin_name.replace(' ', "-"));
+    cmd.mut_subcommand("man", |man| {
+        man.mut_arg("out_dir", |arg| {
+            arg.help(format!(
+                "Write {} and a page for each subcommand into DIR",
+                page
+            ))
+        })
+    })
+}
+
+/// `cargo` with only the `boots` subcommand, for completing `cargo boots ...`
+fn cargo_command() -> clap::Command {
+    clap::Command::new("cargo")
+        .bin_name("cargo")
+        .subcommand_required(true)
+        .subcommand(command("cargo boots").name("boots"))
+}
+
+/// `true` when a completion script asks about a `cargo ...` command line
+fn completing_cargo() -> bool {
+    env::args()
+        .skip_while(|arg| arg != "--")
+        .nth(1)
+        .is_some_and(|word| Path::new(&word).file_stem() == Some(OsStr::new("cargo")))
 }
 
 fn main() -> Result<()> {
+    // Answers the `COMPLETE=<shell> boots -- <words>` calls of `boots completions` scripts,
+    // where the words start with `cargo boots` for the script of `cargo boots completions`
+    CompleteEnv::with_factory(|| match completing_cargo() {
+        true => cargo_command(),
+        false => command("boots"),
+    })
+    .complete();
+
     let args: Vec<String> = env::args().collect();
 
     // When called via `cargo boots`, cargo passes "boots" as first argument
@@ -109,11 +509,7 @@ fn main() -> Result<()> {
         "boots"
     };
 
-    // Build command with dynamic bin_name and examples
-    let cmd = Cli::command()
-        .bin_name(bin_name)
-        .after_help(examples(bin_name));
-
+    let cmd = command(bin_name);
     let cli = if is_cargo_subcommand {
         let matches = cmd.get_matches_from(args.iter().take(1).chain(args.iter().skip(2)));
         Cli::from_arg_matches(&matches)?
@@ -122,24 +518,544 @@ fn main() -> Result<()> {
         Cli::from_arg_matches(&matches)?
     };
 
-    let config = match cli.command {
-        Commands::Service { name, options } => {
-            parse_options(ProjectType::Service, &name, options.as_deref())?
+    // Handled before reading the user config, so a broken config file can still be fixed
+    match cli.command {
+        Commands::Config { action } => return config(action),
+        Commands::Completions { shell } => return completions(&shell, is_cargo_subcommand),
+        Commands::Man { out_dir } => return man(command(bin_name), out_dir),
+        Commands::Remove { module } => {
+            if cli.dry_run {
+                anyhow::bail!("--dry-run is not supported by remove");
+            }
+            return remove_module(module);
+        }
+        _ => {}
+    }
+    // Commands on an existing project only take preferences from the user config, so a broken
+    // one is reported but does not stop them
+    let defaults = match UserConfig::resolve() {
+        Ok(defaults) => defaults,
+        Err(e) if cli.command.is_project_command() => {
+            eprintln!("warning: ignoring the user config: {}", e);
+            UserConfig::default()
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // Layering order: --template-dir over --template-pack over the built-ins. Without either,
+    // existing projects use the packs recorded in their .boots.toml, not the configured one
+    let pack = match cli.command.is_project_command() {
+        true => cli.template_pack,
+        false => cli.template_pack.or(defaults.template_pack.clone()),
+    };
+    let templates = match (pack, cli.template_dir) {
+        (None, None) => None,
+        (pack, dir) => {
+            let mut templates = TemplateSource::embedded();
+            if let Some(pack) = pack {
+                templates =
+                    templates.with_pack(TemplatePack::load(&pack, cli.pack_ref.as_deref())?);
+            }
+            if let Some(dir) = dir {
+                templates = templates.with_dir(dir)?;
+            }
+            Some(templates)
+        }
+    };
+
+    let (mut config, args) = match cli.command {
+        Commands::Add { module, options } => {
+            return add_module(
+                module,
+                options.as_deref(),
+                &defaults,
+                templates,
+                cli.dry_run,
+            );
+        }
+        Commands::Diff {
+            project_type,
+            options,
+        } => return diff(project_type, options.as_deref(), &defaults, templates),
+        Commands::Upgrade { from } => return upgrade(from, templates, cli.dry_run),
+        Commands::List { what, json } => {
+            let listing = match what {
+                ListAction::Types => list::types(),
+                ListAction::Options { project_type } => list::options(project_type),
+                ListAction::Modules => list::modules(),
+                ListAction::Templates => {
+                    list::templates(&templates.unwrap_or_else(TemplateSource::embedded))?
+                }
+            };
+            return listing.print(json);
         }
-        Commands::Cli { name, options } => {
-            parse_options(ProjectType::Cli, &name, options.as_deref())?
+        Commands::Config { .. }
+        | Commands::Completions { .. }
+        | Commands::Man { .. }
+        | Commands::Remove { .. } => unreachable!("handled above"),
+        Commands::Service {
+            name,
+            options,
+            args,
+        } => (
+            parse_options_with(ProjectType::Service, &name, options.as_deref(), &defaults)?,
+            args,
+        ),
+        Commands::Cli {
+            name,
+            options,
+            args,
+        } => (
+            parse_options_with(ProjectType::Cli, &name, options.as_deref(), &defaults)?,
+            args,
+        ),
+        Commands::Lib { name, args } => (
+            parse_options_with(ProjectType::Lib, &name, None, &defaults)?,
+            args,
+        ),
+        Commands::New {
+            config: Some(config),
+            args,
+        } => (load_config(&config, &defaults)?, args),
+        Commands::New { config: None, args } => {
+            if !io::stdin().is_terminal() {
+                anyhow::bail!(
+                    "{} new asks for the project settings but stdin is not a terminal; \
+                    pass --config FILE or use `{} service|cli|lib NAME`",
+                    bin_name,
+                    bin_name
+                );
+            }
+            let mut wizard = Wizard::new(io::stdin().lock(), io::stdout(), defaults.clone());
+            match wizard.run(bin_name)? {
+                Some(config) => (config, args),
+                None => {
+                    println!("Cancelled, nothing was generated");
+                    return Ok(());
+                }
+            }
         }
-        Commands::Lib { name } => parse_options(ProjectType::Lib, &name, None)?,
-        Commands::Sample { name, options } => {
+        Commands::Sample {
+            name,
+            options,
+            args,
+        } => {
             // Default to 'sample' option if none provided
             let opts = options.unwrap_or_else(|| "sample".to_string());
-            parse_options(ProjectType::Sample, &name, Some(&opts))?
+            (
+                parse_options_with(ProjectType::Sample, &name, Some(&opts), &defaults)?,
+                args,
+            )
         }
     };
 
-    let generator = ProjectGenerator::new(config.clone());
-    generator.generate(&env::current_dir()?)?;
+    args.apply(&mut config, &defaults);
+
+    let mut generator = ProjectGenerator::new(config.clone())
+        .with_templates(templates.unwrap_or_else(TemplateSource::embedded))
+        .with_hooks(args.hooks(&defaults));
+    if args.no_hooks {
+        generator = generator.no_hooks();
+    }
+    let hooks = generator.hooks();
+
+    let plan = match &args.path {
+        Some(path) => generator.plan_at(path)?,
+        None => generator.plan(&env::current_dir()?)?,
+    };
+
+    if cli.dry_run {
+        print_plan(&plan);
+        if args.path.is_some() && !plan.conflicts().is_empty() {
+            println!();
+            list_conflicts(&plan, args.on_conflict);
+        }
+        if !hooks.is_empty() {
+            println!();
+            println!("Hooks:");
+            for hook in &hooks {
+                println!("  {}", hook);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.path.is_some() {
+        list_conflicts(&plan, args.on_conflict);
+        if args.on_conflict == ConflictPolicy::Abort && !plan.conflicts().is_empty() {
+            anyhow::bail!(
+                "nothing was written; rerun with --on-conflict skip or --on-conflict overwrite"
+            );
+        }
+        plan.apply_into(args.on_conflict)?;
+    } else {
+        cleanup_on_interrupt(&plan)?;
+        plan.apply()?;
+    }
+
+    for hook in &hooks {
+        println!("Running {}", hook);
+        hook.run(&plan.root).with_context(|| {
+            format!(
+                "project '{}' was created in {}, but its hooks did not finish",
+                config.name,
+                plan.root.display()
+            )
+        })?;
+    }
 
     println!("Project '{}' created successfully!", config.name);
     Ok(())
 }
+
+fn config(action: ConfigAction) -> Result<()> {
+    let path = UserConfig::path().context("no home directory to keep the config file in")?;
+    match action {
+        ConfigAction::Get { key } => match UserConfig::resolve()?.get(&key)? {
+            Some(value) => println!("{}", value),
+            None => anyhow::bail!("'{}' is not set", key),
+        },
+        ConfigAction::Set { key, value } => {
+            let mut config = UserConfig::load_from(&path)?;
+            config.set(&key, &value)?;
+            config.save_key(&path, &key)?;
+            match config.get(&key)? {
+                Some(value) => println!("Set {} = {} in {}", key, value, path.display()),
+                None => println!("Removed {} from {}", key, path.display()),
+            }
+        }
+        ConfigAction::List => {
+            let layers = [
+                (UserConfig::load_from(&path)?, path.display().to_string()),
+                (UserConfig::from_env()?, "environment".to_string()),
+                (UserConfig::from_git(), "git config".to_string()),
+            ];
+            for key in UserConfig::KEYS {
+                let value = layers
+                    .iter()
+                    .find_map(|(layer, source)| Some((layer.get(key).ok()??, source)));
+                match value {
+                    Some((value, source)) => println!("{:<15}{}  ({})", key, value, source),
+                    None => println!("{:<15}(not set)", key),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the script registering completions for `boots`, or for `cargo` when run as
+/// `cargo boots`; the latter only completes `cargo boots ...`, in place of cargo's own
+fn completions(shell: &str, cargo: bool) -> Result<()> {
+    let shells = Shells::builtins();
+    let shell = shells
+        .completer(shell)
+        .with_context(|| format!("unsupported shell: {}", shell))?;
+    let exe = env::current_exe().context("cannot locate the boots executable")?;
+    let (name, bin) = match cargo {
+        true => ("cargo-boots", "cargo"),
+        false => ("boots", "boots"),
+    };
+    shell.write_registration(
+        "COMPLETE",
+        name,
+        bin,
+        &exe.to_string_lossy(),
+        &mut io::stdout(),
+    )?;
+    Ok(())
+}
+
+/// Render the man page of `cmd` to stdout, or every page into `out_dir`
+fn man(cmd: clap::Command, out_dir: Option<PathBuf>) -> Result<()> {
+    match out_dir {
+        Some(dir) => {
+            fs::create_dir_all(&dir)?;
+            clap_mangen::generate_to(cmd, &dir)?;
+            println!("Wrote man pages to {}", dir.display());
+        }
+        None => clap_mangen::Man::new(cmd).render(&mut io::stdout())?,
+    }
+    Ok(())
+}
+
+/// Files of `plan` that already exist in the directory and what `policy` does with them
+fn list_conflicts(plan: &GenerationPlan, policy: ConflictPolicy) {
+    let conflicts = plan.conflicts();
+    if conflicts.is_empty() {
+        return;
+    }
+
+    let count = match conflicts.len() {
+        1 => "1 file already exists".to_string(),
+        n => format!("{} files already exist", n),
+    };
+    println!("{} in {}:", count, plan.root.display());
+    for path in &conflicts {
+        println!("  {}", path.display());
+    }
+    match policy {
+        ConflictPolicy::Skip => println!("Keeping them (--on-conflict skip)"),
+        ConflictPolicy::Overwrite => println!("Overwriting them (--on-conflict overwrite)"),
+        ConflictPolicy::Abort => {}
+    }
+}
+
+fn add_module(
+    module: Module,
+    options: Option<&str>,
+    defaults: &UserConfig,
+    templates: Option<TemplateSource>,
+    dry_run: bool,
+) -> Result<()> {
+    let workspace = Workspace::discover(&env::current_dir()?)?;
+    let config = workspace.config_for(module, options, defaults)?;
+    let generator = ProjectGenerator::new(config.clone())
+        .with_templates(project_templates(&workspace, templates)?);
+    let plan = generator.plan_module(&workspace.root, &workspace.config()?, module)?;
+
+    if dry_run {
+        print_plan(&plan);
+        return Ok(());
+    }
+
+    for path in workspace.add_module(module, &config, &plan)? {
+        println!("Updated {}", path.display());
+    }
+    println!(
+        "Module '{}' added to '{}' at crates/{}",
+        module, workspace.name, module
+    );
+    Ok(())
+}
+
+/// `templates` from the command line, or else those the project was generated from
+fn project_templates(
+    workspace: &Workspace,
+    templates: Option<TemplateSource>,
+) -> Result<TemplateSource> {
+    match (templates, &workspace.manifest) {
+        (Some(templates), _) => Ok(templates),
+        (None, Some(manifest)) => Ok(manifest.templates()?),
+        (None, None) => Ok(TemplateSource::embedded()),
+    }
+}
+
+fn remove_module(module: Module) -> Result<()> {
+    let workspace = Workspace::discover(&env::current_dir()?)?;
+    let removed = workspace.remove_module(module)?;
+
+    for path in &removed.edited {
+        println!("Updated {}", path.display());
+    }
+    println!("Module '{}' removed from '{}'", module, workspace.name);
+
+    if !removed.references.is_empty() {
+        println!();
+        println!("These lines still use the removed crate and need to be fixed:");
+        for reference in &removed.references {
+            println!(
+                "  {}:{}: {}",
+                reference.path.display(),
+                reference.line,
+                reference.text
+            );
+        }
+    }
+    Ok(())
+}
+
+fn diff(
+    project_type: Option<ProjectType>,
+    options: Option<&str>,
+    defaults: &UserConfig,
+    templates: Option<TemplateSource>,
+) -> Result<()> {
+    let workspace = Workspace::discover(&env::current_dir()?)?;
+    let templates = project_templates(&workspace, templates)?;
+    let config = if project_type.is_some() || options.is_some() {
+        let project_type = project_type.unwrap_or(workspace.project_type);
+        workspace.config_with(project_type, options, defaults)?
+    } else {
+        workspace.config()?
+    };
+    let plan = ProjectGenerator::new(config)
+        .with_templates(templates)
+        .plan_at(&workspace.root)?;
+
+    let (mut modified, mut deleted, mut added) = (0, 0, 0);
+    for file in workspace.diff(&plan)? {
+        match file.status {
+            DiffStatus::Modified => modified += 1,
+            DiffStatus::Deleted => {
+                deleted += 1;
+                println!("# deleted from the project: {}", file.path.display());
+            }
+            DiffStatus::Added => {
+                added += 1;
+                println!("# boots would add: {}", file.path.display());
+            }
+        }
+        print!("{}", file.diff);
+    }
+
+    if modified + deleted + added == 0 {
+        println!("'{}' matches a fresh generation", workspace.name);
+    } else {
+        println!(
+            "# {} modified, {} deleted, {} to add",
+            modified, deleted, added
+        );
+    }
+    Ok(())
+}
+
+fn upgrade(from: Option<PathBuf>, templates: Option<TemplateSource>, dry_run: bool) -> Result<()> {
+    let workspace = Workspace::discover(&env::current_dir()?)?;
+    let templates = project_templates(&workspace, templates)?;
+    let Some(manifest) = &workspace.manifest else {
+        anyhow::bail!(
+            "{} has no .boots.toml recording how it was generated, so it cannot be upgraded",
+            workspace.root.display()
+        );
+    };
+    let config = manifest.config.clone();
+
+    let base = match from {
+        Some(boots) => generate_with(&boots, &config, &manifest.packs)?,
+        None if manifest.boots_version == env!("CARGO_PKG_VERSION") => {
+            ProjectGenerator::new(config.clone())
+                .with_templates(manifest.templates()?)
+                .plan_at(&workspace.root)?
+        }
+        None => generate_with(
+            &install_boots(&manifest.boots_version)?,
+            &config,
+            &manifest.packs,
+        )?,
+    };
+    let plan = ProjectGenerator::new(config)
+        .with_templates(templates)
+        .plan_at(&workspace.root)?;
+    let upgrade = Upgrade::new(&workspace, &base, &plan)?;
+
+    println!(
+        "{} '{}' from boots {} to {}",
+        if dry_run {
+            "Dry run: would upgrade"
+        } else {
+            "Upgrading"
+        },
+        workspace.name,
+        upgrade.from_version,
+        upgrade.to_version
+    );
+    if upgrade.files.is_empty() {
+        println!("No template changes to apply");
+    } else {
+        println!();
+    }
+
+    let (mut clean, mut conflicted, mut skipped) = (0, 0, 0);
+    for file in &upgrade.files {
+        let path = file.path.display();
+        match file.status {
+            UpgradeStatus::Updated => println!("  {:<10}{}", "updated", path),
+            UpgradeStatus::Added => println!("  {:<10}{}", "added", path),
+            UpgradeStatus::Merged => println!("  {:<10}{}", "merged", path),
+            UpgradeStatus::Conflicted(n) => {
+                let plural = if n == 1 { "" } else { "s" };
+                println!("  {:<10}{} ({} conflict{})", "conflict", path, n, plural)
+            }
+            UpgradeStatus::Skipped(reason) => {
+                println!("  {:<10}{} ({})", "skipped", path, reason)
+            }
+        }
+        match file.status {
+            UpgradeStatus::Conflicted(_) => conflicted += 1,
+            UpgradeStatus::Skipped(_) => skipped += 1,
+            _ => clean += 1,
+        }
+    }
+    if !upgrade.files.is_empty() {
+        println!();
+        println!(
+            "{} clean, {} conflicted, {} skipped",
+            clean, conflicted, skipped
+        );
+    }
+
+    if !dry_run {
+        upgrade.apply()?;
+        if conflicted > 0 {
+            println!("Resolve the <<<<<<< conflict markers before building");
+        }
+    }
+    Ok(())
+}
+
+/// Install boots `version` into the temp directory, once, to regenerate a project's
+/// original output
+fn install_boots(version: &str) -> Result<PathBuf> {
+    let root = env::temp_dir().join(format!("boots-{}", version));
+    let bin = root
+        .join("bin")
+        .join(format!("boots{}", env::consts::EXE_SUFFIX));
+    if !bin.exists() {
+        println!(
+            "Installing boots {} to regenerate the original output...",
+            version
+        );
+        let status = process::Command::new("cargo")
+            .args(["install", "boots", "--quiet", "--version"])
+            .arg(format!("={}", version))
+            .arg("--root")
+            .arg(&root)
+            .status()?;
+        if !status.success() {
+            anyhow::bail!(
+                "could not install boots {}; pass its executable with --from",
+                version
+            );
+        }
+    }
+    Ok(bin)
+}
+
+/// Ctrl-C while applying `plan`: remove the partially written staging directory
+fn cleanup_on_interrupt(plan: &GenerationPlan) -> Result<()> {
+    let staging = plan.staging_dir();
+    ctrlc::set_handler(move || {
+        let _ = fs::remove_dir_all(&staging);
+        process::exit(130);
+    })?;
+    Ok(())
+}
+
+fn print_plan(plan: &GenerationPlan) {
+    println!("Dry run: would create {}", plan.root.display());
+    println!();
+    for dir in plan.directories() {
+        println!("  {:>8}  {}/", "dir", dir.display());
+    }
+    for file in &plan.files {
+        let source = match &file.template {
+            Some(template) => template.as_str(),
+            None if file.content.is_empty() => "(empty)",
+            None => "(generated)",
+        };
+        println!(
+            "  {:>8}  {}  <- {}",
+            file.content.len(),
+            file.path.display(),
+            source
+        );
+    }
+    println!();
+    println!(
+        "{} directories, {} files, {} bytes",
+        plan.directories().len(),
+        plan.files.len(),
+        plan.size()
+    );
+}
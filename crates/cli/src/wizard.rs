@@ -0,0 +1,249 @@
+use anyhow::{Result, bail};
+use boots_core::config::{OptionKind, options_for, parse_options_with};
+use boots_core::{ProjectConfig, ProjectType, UserConfig, validate_name};
+use std::io::{BufRead, Write};
+
+/// Prompts for the settings of `boots new` when no `--config` is given
+pub struct Wizard<R, W> {
+    input: R,
+    output: W,
+    /// User defaults, offered as the answers to the persistence, frontend and author prompts
+    defaults: UserConfig,
+}
+
+impl<R: BufRead, W: Write> Wizard<R, W> {
+    pub fn new(input: R, output: W, defaults: UserConfig) -> Self {
+        Self {
+            input,
+            output,
+            defaults,
+        }
+    }
+
+    /// Ask for the project, print a summary with the equivalent command and return the
+    /// config, or `None` if generation was declined
+    pub fn run(&mut self, bin_name: &str) -> Result<Option<ProjectConfig>> {
+        let types: Vec<&str> = ProjectType::ALL.iter().map(|t| t.as_str()).collect();
+        let project_type: ProjectType = self.choose("Project type", &types, "service")?;
+        let name = loop {
+            let name = self.ask("Project name", "")?;
+            match validate_name(&name) {
+                Ok(()) => break name,
+                Err(e) => writeln!(self.output, "  {}", e)?,
+            }
+        };
+
+        // Answers become `--options` so they go through the same checks as the command line
+        let persistence = self.defaults.persistence.map_or("none", |p| p.as_str());
+        let frontend = self.defaults.frontend.map_or("none", |f| f.as_str());
+        let specs = options_for(project_type);
+        let choices = |group: fn(&OptionKind) -> bool| -> Vec<&str> {
+            std::iter::once("none")
+                .chain(specs.iter().filter(|s| group(&s.kind)).map(|s| s.name))
+                .collect()
+        };
+        let offered = |choices: &[&str], default: &'static str| {
+            if choices.contains(&default) {
+                default
+            } else {
+                "none"
+            }
+        };
+
+        let mut options = Vec::new();
+        let backends = choices(|kind| matches!(kind, OptionKind::Persistence(Some(_))));
+        if backends.len() > 1 {
+            let default = offered(&backends, persistence);
+            options.push(self.choose("Persistence", &backends, default)?);
+        }
+        let frontends = choices(|kind| matches!(kind, OptionKind::Frontend(Some(_))));
+        if frontends.len() > 1 {
+            let default = offered(&frontends, frontend);
+            options.push(self.choose("Frontend", &frontends, default)?);
+        }
+        for spec in &specs {
+            let question = match spec.kind {
+                OptionKind::Grpc => "gRPC",
+                OptionKind::Client => "HTTP client",
+                OptionKind::Sample => {
+                    options.push(spec.name.to_string());
+                    continue;
+                }
+                // Only a gRPC service without a frontend can do without HTTP
+                OptionKind::Http => {
+                    let chosen =
+                        |names: &[&str]| options.iter().any(|o| names.contains(&o.as_str()));
+                    if chosen(&["grpc"])
+                        && !chosen(&frontends[1..])
+                        && !self.confirm("HTTP API", true)?
+                    {
+                        options.push(format!("no-{}", spec.name));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            if self.confirm(question, false)? {
+                options.push(spec.name.to_string());
+            }
+        }
+        options.retain(|o| o != "none");
+        let options = options.join(",");
+        let mut config = parse_options_with(project_type, &name, Some(&options), &self.defaults)?;
+
+        config.author_name = self.ask("Author name", &config.author_name)?;
+        config.author_email = self.ask("Author email", &config.author_email)?;
+
+        writeln!(self.output)?;
+        writeln!(self.output, "  {:<10}{}", "type", config.project_type)?;
+        writeln!(self.output, "  {:<10}{}", "name", config.name)?;
+        let modules: Vec<&str> = config.modules().iter().map(|m| m.as_str()).collect();
+        writeln!(self.output, "  {:<10}{}", "modules", modules.join(", "))?;
+        if !options.is_empty() {
+            writeln!(self.output, "  {:<10}{}", "options", options)?;
+        }
+        writeln!(
+            self.output,
+            "  {:<10}{} <{}>",
+            "author", config.author_name, config.author_email
+        )?;
+        writeln!(self.output)?;
+
+        let mut command = format!("{} {} {}", bin_name, config.project_type, config.name);
+        if !options.is_empty() && !matches!(project_type, ProjectType::Sample) {
+            command.push_str(&format!(" --options {}", options));
+        }
+        writeln!(self.output, "Equivalent command: {}", command)?;
+
+        if self.confirm("Generate", true)? {
+            Ok(Some(config))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Print `question [default]: ` and read a line, falling back to `default` when empty
+    fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            write!(self.output, "{}: ", question)?;
+        } else {
+            write!(self.output, "{} [{}]: ", question, default)?;
+        }
+        self.output.flush()?;
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            bail!("input ended before the project settings were complete");
+        }
+        let answer = line.trim();
+        Ok(if answer.is_empty() { default } else { answer }.to_string())
+    }
+
+    /// Ask until the answer is one of `choices`
+    fn choose<T: std::str::FromStr>(
+        &mut self,
+        question: &str,
+        choices: &[&str],
+        default: &str,
+    ) -> Result<T> {
+        let question = format!("{} ({})", question, choices.join("/"));
+        loop {
+            let answer = self.ask(&question, default)?;
+            match answer.parse() {
+                Ok(value) if choices.contains(&answer.as_str()) => return Ok(value),
+                _ => writeln!(self.output, "  choose one of: {}", choices.join(", "))?,
+            }
+        }
+    }
+
+    /// Ask a yes/no question until the answer is one
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let question = format!("{}? ({})", question, if default { "Y/n" } else { "y/N" });
+        loop {
+            match self.ask(&question, "")?.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "  answer y or n")?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boots_core::PersistenceType;
+
+    fn run(answers: &str) -> (Result<Option<ProjectConfig>>, String) {
+        let mut output = Vec::new();
+        let defaults = UserConfig {
+            author: Some("Git <git@example.com>".to_string()),
+            persistence: Some(PersistenceType::File),
+            ..UserConfig::default()
+        };
+        let result = Wizard::new(answers.as_bytes(), &mut output, defaults).run("boots");
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_service_answers() {
+        let (config, output) = run("\nMy.Api\nmy-api\npostgres\n\ny\n\nJane\njane@example.com\n\n");
+        let config = config.unwrap().unwrap();
+        assert_eq!(config.project_type, ProjectType::Service);
+        assert_eq!(config.name, "my-api");
+        assert_eq!(config.persistence, Some(PersistenceType::Postgres));
+        assert!(config.frontend.is_none());
+        assert!(config.has_grpc);
+        assert_eq!(config.author_name, "Jane");
+        assert!(output.contains("(try 'my-api')"));
+        assert!(
+            output.contains("Equivalent command: boots service my-api --options postgres,grpc")
+        );
+    }
+
+    #[test]
+    fn test_invalid_choice_is_asked_again() {
+        let (config, output) = run("cli\ntool\nmysql\nsqlite\nmaybe\nyes\nJane\nj@x\nn\n");
+        assert!(
+            config.unwrap().is_none(),
+            "declined generation returns None"
+        );
+        assert!(output.contains("choose one of: none, postgres, sqlite, file"));
+        assert!(output.contains("answer y or n"));
+        assert!(output.contains("Equivalent command: boots cli tool --options sqlite,client"));
+    }
+
+    #[test]
+    fn test_defaults_are_offered() {
+        let (config, output) = run("cli\ntool\n\n\n\n\n\n");
+        let config = config.unwrap().unwrap();
+        assert_eq!(config.persistence, Some(PersistenceType::File));
+        assert_eq!(config.author_name, "Git");
+        assert!(output.contains("Persistence (none/postgres/sqlite/file) [file]: "));
+        assert!(output.contains("Author email [git@example.com]: "));
+    }
+
+    #[test]
+    fn test_service_choices_come_from_the_option_table() {
+        let (config, output) = run("service\napi\n\n\n\n\n\n\n");
+        let config = config.unwrap().unwrap();
+        assert_eq!(config.persistence, Some(PersistenceType::File));
+        assert!(output.contains("Persistence (none/postgres/sqlite/file) [file]: "));
+        assert!(output.contains("Frontend (none/spa/ssr) [none]: "));
+    }
+
+    #[test]
+    fn test_grpc_only_service() {
+        let (config, output) = run("service\napi\nnone\nnone\ny\nn\n\n\n\n");
+        let config = config.unwrap().unwrap();
+        assert!(config.has_grpc && !config.has_http);
+        assert!(output.contains("Equivalent command: boots service api --options grpc,no-http"));
+    }
+
+    #[test]
+    fn test_input_ending_early_is_an_error() {
+        let (config, _) = run("lib\n");
+        assert!(config.is_err());
+    }
+}
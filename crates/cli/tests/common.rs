@@ -42,12 +42,60 @@ impl From<Output> for CommandResult {
     }
 }
 
-pub fn run_boots_command(args: &[&str], cwd: &Path) -> CommandResult {
+/// A boots command that ignores the developer's user config, `BOOTS_*` variables and git
+/// identity: `HOME` and `XDG_CONFIG_HOME` point at an empty directory, cargo and rustup keep
+/// their real homes
+fn boots_command(args: &[&str], cwd: &Path) -> Command {
     let boots_bin = env!("CARGO_BIN_EXE_cargo-boots");
-
-    let output = Command::new(boots_bin)
+    let home = std::env::temp_dir().join(format!("boots-test-home-{}", std::process::id()));
+    std::fs::create_dir_all(&home).expect("Failed to create test home");
+    let real_home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let mut command = Command::new(boots_bin);
+    command
         .args(args)
         .current_dir(cwd)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env(
+            "CARGO_HOME",
+            std::env::var_os("CARGO_HOME").unwrap_or_else(|| real_home.join(".cargo").into()),
+        )
+        .env(
+            "RUSTUP_HOME",
+            std::env::var_os("RUSTUP_HOME").unwrap_or_else(|| real_home.join(".rustup").into()),
+        )
+        .env("GIT_CONFIG_COUNT", "2")
+        .env("GIT_CONFIG_KEY_0", "user.name")
+        .env("GIT_CONFIG_VALUE_0", "Boots Test")
+        .env("GIT_CONFIG_KEY_1", "user.email")
+        .env("GIT_CONFIG_VALUE_1", "test@example.com");
+    for (key, _) in std::env::vars_os() {
+        if key.to_string_lossy().starts_with("BOOTS_") {
+            command.env_remove(key);
+        }
+    }
+    command
+}
+
+pub fn run_boots_command(args: &[&str], cwd: &Path) -> CommandResult {
+    let output = boots_command(args, cwd)
+        .output()
+        .expect("Failed to run boots");
+
+    CommandResult::from(output)
+}
+
+/// Run boots with extra environment variables, e.g. `BOOTS_AUTHOR` or another `XDG_CONFIG_HOME`
+pub fn run_boots_command_with_env(
+    args: &[&str],
+    cwd: &Path,
+    env: &[(&str, &str)],
+) -> CommandResult {
+    let output = boots_command(args, cwd)
+        .envs(env.iter().copied())
         .output()
         .expect("Failed to run boots");
 
@@ -100,10 +148,10 @@ pub async fn wait_for_server(url: &str, max_wait: Duration) -> bool {
     let start = std::time::Instant::now();
 
     while start.elapsed() < max_wait {
-        if let Ok(resp) = client.get(url).send().await {
-            if resp.status().is_success() {
-                return true;
-            }
+        if let Ok(resp) = client.get(url).send().await
+            && resp.status().is_success()
+        {
+            return true;
         }
         tokio::time::sleep(Duration::from_millis(100)).await;
     }
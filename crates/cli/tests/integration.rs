@@ -213,6 +213,7 @@ async fn test_service_runtime() {
 
     // Cleanup
     server.kill().ok();
+    server.wait().ok();
 }
 
 // gRPC Test
@@ -253,6 +254,33 @@ fn test_service_with_grpc() {
     );
 }
 
+#[test]
+fn test_service_grpc_only() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-grpc-only",
+            "--options",
+            "grpc,no-http",
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-grpc-only");
+    assert!(project.join("crates/api/src/grpc.rs").exists());
+    assert!(!project.join("crates/api/src/routes.rs").exists());
+    let api_cargo = std::fs::read_to_string(project.join("crates/api/Cargo.toml")).unwrap();
+    assert!(!api_cargo.contains("axum"));
+    let server = std::fs::read_to_string(project.join("crates/runtime/src/server.rs")).unwrap();
+    assert!(server.contains("tonic::transport::Server::builder()"));
+    assert!(!server.contains("axum"));
+    let manifest = std::fs::read_to_string(project.join(".boots.toml")).unwrap();
+    assert!(manifest.contains("http = false"));
+}
+
 #[test]
 fn test_service_with_postgres_env() {
     let temp = TempProject::new();
@@ -367,9 +395,9 @@ fn test_sample_default_options() {
 }
 
 #[test]
-fn test_sample_ignores_other_options() {
+fn test_sample_rejects_other_options() {
     let temp = TempProject::new();
-    // sample option should ignore other options like sqlite
+    // sample is a fixed configuration, so other options are an error rather than ignored
     let result = run_boots_command(
         &[
             "boots",
@@ -380,14 +408,1033 @@ fn test_sample_ignores_other_options() {
         ],
         temp.path(),
     );
+    assert!(!result.success, "sample,sqlite should be rejected");
+    assert!(
+        result
+            .stderr
+            .contains("'sqlite' does not apply to sample projects"),
+        "unexpected error: {}",
+        result.stderr
+    );
+    assert!(!project_path(&temp, "test-board-ignore").exists());
+}
+
+// Template Override Tests
+
+#[test]
+fn test_template_dir_overrides_builtin() {
+    let temp = TempProject::new();
+    let overrides = temp.path().join("company-templates");
+    std::fs::create_dir_all(overrides.join("docker")).unwrap();
+    std::fs::write(
+        overrides.join("docker/Dockerfile"),
+        "FROM registry.example.com/rust\nRUN cargo build -p {{project_name}}-cli\n",
+    )
+    .unwrap();
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-override",
+            "--template-dir",
+            overrides.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
     assert!(result.success, "Generation failed: {}", result.stderr);
 
-    let project = project_path(&temp, "test-board-ignore");
+    let project = project_path(&temp, "test-override");
+    let dockerfile = std::fs::read_to_string(project.join("Dockerfile")).unwrap();
+    assert!(
+        dockerfile.contains("FROM registry.example.com/rust"),
+        "Dockerfile not overridden"
+    );
+    assert!(
+        dockerfile.contains("test-override-cli"),
+        "override not rendered"
+    );
+
+    // Files missing from the override directory fall back to the built-ins
+    assert!(project.join("Makefile").exists(), "Makefile missing");
+}
 
-    // Should use postgres (sample default), not sqlite
-    let compose_content = std::fs::read_to_string(project.join("docker-compose.yml")).unwrap();
+#[test]
+fn test_template_dir_missing() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "lib",
+            "test-missing-dir",
+            "--template-dir",
+            "does-not-exist",
+        ],
+        temp.path(),
+    );
+    assert!(!result.success, "Generation should fail");
+    assert!(result.stderr.contains("template directory not found"));
+}
+
+#[test]
+fn test_template_pack() {
+    let temp = TempProject::new();
+    let pack = temp.path().join("company-pack");
+    std::fs::create_dir_all(pack.join("github")).unwrap();
+    std::fs::write(
+        pack.join("pack.toml"),
+        "[pack]\nname = \"company\"\nversion = \"1.0.0\"\nproject_types = [\"service\"]\nmodules = [\"api\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        pack.join("github/test.yml"),
+        "name: Company Test\n# {{project_name}}\n",
+    )
+    .unwrap();
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "list",
+            "templates",
+            "--template-pack",
+            pack.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "list failed: {}", result.stderr);
+    let header = result.stdout.lines().next().unwrap();
+    assert!(header.starts_with("# pack company 1.0.0 from "));
+    assert!(header.ends_with("modules: api"));
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-pack",
+            "--template-pack",
+            pack.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-pack");
+    let workflow = std::fs::read_to_string(project.join(".github/workflows/test.yml")).unwrap();
+    assert!(
+        workflow.contains("Company Test") && workflow.contains("# test-pack"),
+        "test.yml not taken from pack"
+    );
+
+    // The pack is recorded with its location, so diff regenerates with it
+    let manifest = std::fs::read_to_string(project.join(".boots.toml")).unwrap();
+    assert!(manifest.contains("name = \"company\""));
+    assert!(manifest.contains("path = "));
+    let result = run_boots_command(&["boots", "diff"], &project);
+    assert!(result.success, "Diff failed: {}", result.stderr);
+    assert!(result.stdout.contains("matches a fresh generation"));
+
+    // The pack only declares service projects
+    let result = run_boots_command(
+        &[
+            "boots",
+            "lib",
+            "test-pack-lib",
+            "--template-pack",
+            pack.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert!(!result.success, "lib generation should be rejected");
+    assert!(result.stderr.contains("does not support 'lib' projects"));
+}
+
+#[test]
+fn test_template_dir_manifest_adds_files() {
+    let temp = TempProject::new();
+    let templates = temp.path().join("templates");
+    std::fs::create_dir_all(templates.join("company")).unwrap();
+    std::fs::write(
+        templates.join("manifest.toml"),
+        "[[file]]\n\
+         template = \"company/CODEOWNERS\"\n\
+         dest = \".github/CODEOWNERS\"\n\
+         when = 'project_type == \"lib\"'\n\n\
+         [[file]]\n\
+         dest = \"docs/{{project_name}}.md\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        templates.join("company/CODEOWNERS"),
+        "* @{{project_name}}-owners\n",
+    )
+    .unwrap();
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "lib",
+            "test-manifest",
+            "--template-dir",
+            templates.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-manifest");
+    let owners = std::fs::read_to_string(project.join(".github/CODEOWNERS")).unwrap();
+    assert_eq!(owners, "* @test-manifest-owners\n");
+    assert!(project.join("docs/test-manifest.md").exists());
+    assert!(project.join("crates/core/src/lib.rs").exists());
+}
+
+// Dry Run Tests
+
+#[test]
+fn test_dry_run_writes_nothing() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-dry-run",
+            "--options",
+            "grpc",
+            "--dry-run",
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Dry run failed: {}", result.stderr);
+
+    assert!(
+        !project_path(&temp, "test-dry-run").exists(),
+        "dry run created the project"
+    );
+    assert!(
+        result
+            .stdout
+            .contains("Cargo.toml  <- base/Cargo.workspace.toml")
+    );
+    assert!(result.stdout.contains("proto/service.proto"));
+    assert!(result.stdout.contains("crates/api/src/handlers/"));
+}
+
+#[test]
+fn test_failed_generation_rolls_back() {
+    let temp = TempProject::new();
+    let templates = temp.path().join("broken-templates");
+    std::fs::create_dir_all(&templates).unwrap();
+    // `Cargo.toml/` as a directory makes writing the workspace Cargo.toml fail
+    std::fs::write(
+        templates.join("manifest.toml"),
+        "[[file]]\ndest = \"Cargo.toml/conflict\"\n",
+    )
+    .unwrap();
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "lib",
+            "test-rollback",
+            "--template-dir",
+            templates.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert!(!result.success, "Generation should fail");
+    assert!(!project_path(&temp, "test-rollback").exists());
+    let leftovers: Vec<_> = std::fs::read_dir(temp.path())
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().contains("test-rollback"))
+        .collect();
+    assert!(leftovers.is_empty(), "staging directory left behind");
+
+    // Nothing blocks a retry
+    let result = run_boots_command(&["boots", "lib", "test-rollback"], temp.path());
+    assert!(result.success, "Retry failed: {}", result.stderr);
+}
+
+// Add Module Tests
+
+#[test]
+fn test_add_module() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "cli", "test-add"], temp.path());
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-add");
+    let result = run_boots_command(
+        &["boots", "add", "persistence", "--options", "sqlite"],
+        &project,
+    );
+    assert!(result.success, "Add failed: {}", result.stderr);
+
+    let workspace = std::fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(workspace.contains("\"crates/persistence\""));
+    let cli_cargo = std::fs::read_to_string(project.join("crates/cli/Cargo.toml")).unwrap();
+    assert!(cli_cargo.contains("test-add-persistence = { path = \"../persistence\" }"));
+    let persistence_cargo =
+        std::fs::read_to_string(project.join("crates/persistence/Cargo.toml")).unwrap();
+    assert!(persistence_cargo.contains("sqlite"));
+
+    let result = run_boots_command(&["boots", "add", "persistence"], &project);
+    assert!(!result.success, "Adding twice should fail");
+    assert!(result.stderr.contains("already exists"));
+
+    let build = cargo_build(&project);
+    assert!(build.success, "Build failed: {}", build.stderr);
+}
+
+#[test]
+fn test_add_module_matches_fresh_generation() {
+    let temp = TempProject::new();
+    for (args, module) in [
+        (["boots", "service", "test-add-api"], "persistence"),
+        (["boots", "cli", "test-add-tool"], "client"),
+    ] {
+        let result = run_boots_command(&args, temp.path());
+        assert!(result.success, "Generation failed: {}", result.stderr);
+        let project = project_path(&temp, args[2]);
+
+        let mut add = vec!["boots", "add", module];
+        if module == "persistence" {
+            add.extend(["--options", "postgres"]);
+        }
+        let result = run_boots_command(&add, &project);
+        assert!(result.success, "Add failed: {}", result.stderr);
+
+        let result = run_boots_command(&["boots", "diff"], &project);
+        assert!(result.success, "Diff failed: {}", result.stderr);
+        assert!(
+            result.stdout.contains("matches a fresh generation"),
+            "drift after adding {}: {}",
+            module,
+            result.stdout
+        );
+    }
     assert!(
-        compose_content.contains("postgres"),
-        "postgres service missing"
+        project_path(&temp, "test-add-api")
+            .join(".env.example")
+            .is_file()
+    );
+}
+
+#[test]
+fn test_remove_module() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &["boots", "cli", "test-remove", "--options", "client"],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-remove");
+    let result = run_boots_command(&["boots", "remove", "cli"], &project);
+    assert!(!result.success, "Removing a required module should fail");
+    assert!(result.stderr.contains("required by cli projects"));
+
+    let result = run_boots_command(&["boots", "remove", "client"], &project);
+    assert!(result.success, "Remove failed: {}", result.stderr);
+    assert!(!project.join("crates/client").exists());
+    let workspace = std::fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(!workspace.contains("crates/client"));
+}
+
+// Project Manifest Tests
+
+#[test]
+fn test_project_manifest_written() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-manifest-file",
+            "--options",
+            "sqlite,grpc",
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-manifest-file");
+    let manifest = std::fs::read_to_string(project.join(".boots.toml")).unwrap();
+    assert!(manifest.contains(&format!("version = \"{}\"", env!("CARGO_PKG_VERSION"))));
+    assert!(manifest.contains("type = \"service\""));
+    assert!(manifest.contains("persistence = \"sqlite\""));
+    assert!(manifest.contains("grpc = true"));
+    assert!(manifest.contains("\"crates/api/build.rs\" = \"sha256:"));
+}
+
+// Upgrade Tests
+
+#[test]
+fn test_upgrade_merges_template_changes() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "lib", "test-upgrade"], temp.path());
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    // Newer templates: both targets of the Makefile change
+    let templates = temp.path().join("templates");
+    std::fs::create_dir_all(templates.join("base")).unwrap();
+    let makefile = include_str!("../../core/templates/base/Makefile");
+    std::fs::write(
+        templates.join("base/Makefile"),
+        makefile
+            .replace("cargo build --all", "cargo build --workspace")
+            .replace("cargo clean", "cargo clean && rm -rf dist"),
+    )
+    .unwrap();
+
+    // The user edited one of them
+    let project = project_path(&temp, "test-upgrade");
+    let edited = makefile.replace("cargo build --all", "cargo build --release");
+    std::fs::write(project.join("Makefile"), &edited).unwrap();
+
+    let templates = templates.to_string_lossy().to_string();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "upgrade",
+            "--template-dir",
+            &templates,
+            "--dry-run",
+        ],
+        &project,
+    );
+    assert!(result.success, "Dry run failed: {}", result.stderr);
+    assert!(result.stdout.contains("Makefile (1 conflict)"));
+    assert_eq!(
+        std::fs::read_to_string(project.join("Makefile")).unwrap(),
+        edited
+    );
+
+    let result = run_boots_command(
+        &["boots", "upgrade", "--template-dir", &templates],
+        &project,
+    );
+    assert!(result.success, "Upgrade failed: {}", result.stderr);
+    assert!(result.stdout.contains("0 clean, 1 conflicted, 0 skipped"));
+
+    let merged = std::fs::read_to_string(project.join("Makefile")).unwrap();
+    assert!(merged.contains("<<<<<<< yours\n\tcargo build --release\n=======\n"));
+    assert!(merged.contains("cargo clean && rm -rf dist"));
+}
+
+#[test]
+fn test_upgrade_from_ignores_user_defaults() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "lib", "test-upgrade-from"], temp.path());
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    let project = project_path(&temp, "test-upgrade-from");
+    let cargo = std::fs::read_to_string(project.join("Cargo.toml")).unwrap();
+
+    // Defaults that differ from everything the project was generated with
+    let config_home = temp.path().join("home");
+    std::fs::create_dir_all(config_home.join("boots")).unwrap();
+    std::fs::write(
+        config_home.join("boots/config.toml"),
+        "author = \"Configured <configured@example.com>\"\nowner = \"acme\"\n\
+         license = \"Apache-2.0\"\nhooks = \"commit\"\n",
+    )
+    .unwrap();
+    let config_home = config_home.to_string_lossy().to_string();
+
+    let result = run_boots_command_with_env(
+        &[
+            "boots",
+            "upgrade",
+            "--from",
+            env!("CARGO_BIN_EXE_cargo-boots"),
+        ],
+        &project,
+        &[
+            ("XDG_CONFIG_HOME", &config_home),
+            ("BOOTS_AUTHOR", "Env <env@example.com>"),
+        ],
+    );
+    assert!(result.success, "Upgrade failed: {}", result.stderr);
+    assert!(result.stdout.contains("No template changes to apply"));
+    assert_eq!(
+        std::fs::read_to_string(project.join("Cargo.toml")).unwrap(),
+        cargo
+    );
+}
+
+// Diff Tests
+
+#[test]
+fn test_diff_against_fresh_generation() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &["boots", "cli", "test-diff", "--options", "client"],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-diff");
+    let result = run_boots_command(&["boots", "diff"], &project);
+    assert!(result.success, "Diff failed: {}", result.stderr);
+    assert!(result.stdout.contains("matches a fresh generation"));
+
+    let lib = project.join("crates/core/src/lib.rs");
+    let mut content = std::fs::read_to_string(&lib).unwrap();
+    content.push_str("pub fn drifted() {}\n");
+    std::fs::write(&lib, content).unwrap();
+    std::fs::remove_file(project.join("Makefile")).unwrap();
+
+    let result = run_boots_command(&["boots", "diff"], &project);
+    assert!(result.success, "Diff failed: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("--- a/crates/core/src/lib.rs\n+++ b/crates/core/src/lib.rs\n")
+    );
+    assert!(result.stdout.contains("+pub fn drifted() {}\n"));
+    assert!(
+        result
+            .stdout
+            .contains("# deleted from the project: Makefile\n")
+    );
+    assert!(result.stdout.contains("# 1 modified, 1 deleted, 0 to add"));
+
+    // Settings from the command line instead of .boots.toml
+    let result = run_boots_command(
+        &["boots", "diff", "--options", "client,persistence"],
+        &project,
+    );
+    assert!(result.success, "Diff failed: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("# boots would add: crates/persistence/Cargo.toml\n")
+    );
+}
+
+// Existing Directory Tests
+
+#[test]
+fn test_generate_into_existing_directory() {
+    let temp = TempProject::new();
+    let clone = temp.path().join("clone");
+    std::fs::create_dir(&clone).unwrap();
+    std::fs::write(clone.join("README.md"), "# my-api\n").unwrap();
+    std::fs::write(clone.join("LICENSE"), "MIT\n").unwrap();
+
+    let result = run_boots_command(&["boots", "service", "my-api", "--path", "."], &clone);
+    assert!(!result.success, "Conflicts should abort by default");
+    assert!(
+        result
+            .stdout
+            .contains("1 file already exists in .:\n  README.md\n")
+    );
+    assert!(!clone.join("Cargo.toml").exists());
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "my-api",
+            "--into",
+            ".",
+            "--on-conflict",
+            "skip",
+        ],
+        &clone,
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    assert_eq!(
+        std::fs::read_to_string(clone.join("README.md")).unwrap(),
+        "# my-api\n"
+    );
+    assert!(clone.join("LICENSE").exists());
+    assert!(clone.join("crates/api/Cargo.toml").exists());
+
+    let result = run_boots_command(&["boots", "lib", "x", "--on-conflict", "skip"], &clone);
+    assert!(!result.success, "--on-conflict requires --path");
+}
+
+// Hook Tests
+
+#[test]
+fn test_post_generation_hooks() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &["boots", "lib", "test-hooks", "--git", "--fmt"],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("Running git init\nRunning cargo fmt\n")
+    );
+    assert!(project_path(&temp, "test-hooks").join(".git").is_dir());
+
+    let pack = temp.path().join("pack");
+    std::fs::create_dir_all(&pack).unwrap();
+    std::fs::write(
+        pack.join("pack.toml"),
+        "[pack]\nname = \"company\"\nversion = \"1.0.0\"\nhooks = [\"touch hooked\", \"echo nope >&2; exit 1\"]\n",
+    )
+    .unwrap();
+    let pack = pack.to_string_lossy().to_string();
+
+    let result = run_boots_command(
+        &["boots", "lib", "test-pack-hooks", "--template-pack", &pack],
+        temp.path(),
+    );
+    assert!(!result.success, "A failing hook should fail the command");
+    assert!(
+        result
+            .stderr
+            .contains("Hook 'company: echo nope >&2; exit 1' failed: nope")
+    );
+    let project = project_path(&temp, "test-pack-hooks");
+    assert!(project.join("hooked").exists());
+
+    let result = run_boots_command(
+        &[
+            "boots",
+            "lib",
+            "test-no-hooks",
+            "--template-pack",
+            &pack,
+            "--no-hooks",
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    assert!(!project_path(&temp, "test-no-hooks").join("hooked").exists());
+}
+
+// Name Validation Tests
+
+#[test]
+fn test_invalid_project_names() {
+    let temp = TempProject::new();
+    for (name, message) in [
+        ("1api", "must start with a letter (try 'api')"),
+        ("My.Lib", "'.' is not allowed"),
+        ("test", "is reserved (try 'test-app')"),
+        ("my api", "(try 'my-api')"),
+    ] {
+        let result = run_boots_command(&["boots", "lib", name], temp.path());
+        assert!(!result.success, "'{}' should be rejected", name);
+        assert!(
+            result.stderr.contains(message),
+            "unexpected error for '{}': {}",
+            name,
+            result.stderr
+        );
+    }
+    assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+}
+
+// Config File Tests
+
+#[test]
+fn test_generate_from_config_file() {
+    let temp = TempProject::new();
+    std::fs::write(
+        temp.path().join("project.toml"),
+        "name = \"test-spec\"\ntype = \"cli\"\nclient = true\npersistence = \"sqlite\"\nlicense = \"Apache-2.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("project.yaml"),
+        "name: test-spec-yaml\ntype: cli\nclient: true\npersistence: sqlite\nlicense: Apache-2.0\n",
+    )
+    .unwrap();
+
+    for file in ["project.toml", "project.yaml"] {
+        let result = run_boots_command(&["boots", "new", "--config", file], temp.path());
+        assert!(result.success, "Generation failed: {}", result.stderr);
+    }
+
+    let project = project_path(&temp, "test-spec");
+    assert!(project.join("crates/client").exists());
+    assert!(project.join("crates/persistence").exists());
+    let cargo = std::fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(cargo.contains("license = \"Apache-2.0\""));
+
+    // Both formats describe the same project
+    let manifest = |name: &str| {
+        std::fs::read_to_string(project_path(&temp, name).join(".boots.toml"))
+            .unwrap()
+            .split("[files]")
+            .next()
+            .unwrap()
+            .replace(name, "NAME")
+    };
+    assert_eq!(manifest("test-spec"), manifest("test-spec-yaml"));
+
+    std::fs::write(
+        temp.path().join("bad.json"),
+        "{\"name\": \"test-bad\", \"type\": \"service\", \"persistance\": \"sqlite\"}",
+    )
+    .unwrap();
+    let result = run_boots_command(&["boots", "new", "--config", "bad.json"], temp.path());
+    assert!(!result.success, "Unknown keys should be rejected");
+    assert!(result.stderr.contains("unknown field `persistance`"));
+    assert!(!project_path(&temp, "test-bad").exists());
+}
+
+#[test]
+fn test_new_without_config_needs_terminal() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "new"], temp.path());
+    assert!(!result.success, "Prompting without a terminal should fail");
+    assert!(result.stderr.contains("stdin is not a terminal"));
+    assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+}
+
+// User Config Tests
+
+#[test]
+fn test_user_config_defaults() {
+    let temp = TempProject::new();
+    let home = temp.path().join("home");
+    let home = home.to_string_lossy().to_string();
+    let boots = |args: &[&str], env: &[(&str, &str)]| {
+        let mut env = env.to_vec();
+        env.push(("XDG_CONFIG_HOME", &home));
+        run_boots_command_with_env(args, temp.path(), &env)
+    };
+
+    for (key, value) in [
+        ("owner", "acme"),
+        ("license", "Apache-2.0"),
+        ("hooks", "git"),
+    ] {
+        let result = boots(&["boots", "config", "set", key, value], &[]);
+        assert!(result.success, "config set failed: {}", result.stderr);
+    }
+    let result = boots(&["boots", "config", "set", "persistence", "mysql"], &[]);
+    assert!(!result.success, "Invalid values should be rejected");
+    assert!(result.stderr.contains("unknown persistence: mysql"));
+
+    let result = boots(&["boots", "config", "get", "owner"], &[]);
+    assert_eq!(result.stdout.trim(), "acme");
+
+    // The config file wins over the environment, which wins over git config
+    let env = [
+        ("BOOTS_AUTHOR", "Env Author <env@example.com>"),
+        ("BOOTS_LICENSE", "MIT"),
+    ];
+    let result = boots(&["boots", "config", "list"], &env);
+    assert!(result.success, "config list failed: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("Env Author <env@example.com>  (environment)")
+    );
+    assert!(result.stdout.contains("Apache-2.0  ("));
+    assert!(result.stdout.contains("frontend       (not set)"));
+
+    let result = boots(&["boots", "lib", "test-defaults"], &env);
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    let project = project_path(&temp, "test-defaults");
+    let cargo = std::fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(cargo.contains("authors = [\"Env Author <env@example.com>\"]"));
+    assert!(cargo.contains("license = \"Apache-2.0\""));
+    assert!(cargo.contains("repository = \"https://github.com/acme/test-defaults\""));
+    assert!(project.join(".git").is_dir(), "Default hooks should run");
+
+    // Command-line flags replace the default hooks
+    let result = boots(&["boots", "lib", "test-flags", "--no-hooks"], &env);
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    assert!(!project_path(&temp, "test-flags").join(".git").exists());
+}
+
+#[test]
+fn test_broken_user_config_spares_existing_projects() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "cli", "test-broken"], temp.path());
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let config_home = temp.path().join("home");
+    std::fs::create_dir_all(config_home.join("boots")).unwrap();
+    std::fs::write(config_home.join("boots/config.toml"), "owner = [").unwrap();
+    let config_home = config_home.to_string_lossy().to_string();
+    let env = [("XDG_CONFIG_HOME", config_home.as_str())];
+
+    let result = run_boots_command_with_env(&["boots", "lib", "test-new"], temp.path(), &env);
+    assert!(
+        !result.success,
+        "Generation should report the broken config"
+    );
+
+    let project = project_path(&temp, "test-broken");
+    for args in [
+        &["boots", "add", "client"][..],
+        &["boots", "diff"],
+        &["boots", "remove", "client"],
+    ] {
+        let result = run_boots_command_with_env(args, &project, &env);
+        assert!(result.success, "{:?} failed: {}", args, result.stderr);
+    }
+}
+
+// Repository Tests
+
+#[test]
+fn test_repository_url_is_propagated() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &[
+            "boots",
+            "service",
+            "test-repo",
+            "--repository",
+            "https://gitlab.com/acme/test-repo.git",
+        ],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+
+    let project = project_path(&temp, "test-repo");
+    let url = "https://gitlab.com/acme/test-repo";
+    let read = |path: &str| std::fs::read_to_string(project.join(path)).unwrap();
+    assert!(read("Cargo.toml").contains(&format!("repository = \"{}\"", url)));
+    assert!(read("README.md").contains(&format!("Source: {}", url)));
+    assert!(
+        !read("README.md").contains("badge.svg"),
+        "Actions badges are GitHub only"
+    );
+    assert!(
+        read(".github/workflows/release.yml")
+            .contains(&format!("{}/tree/${{{{ github.ref_name }}}}", url))
+    );
+    assert!(read("Dockerfile").contains(&format!("org.opencontainers.image.source=\"{}\"", url)));
+
+    let result = run_boots_command(
+        &["boots", "lib", "test-owner", "--repo-owner", "acme"],
+        temp.path(),
+    );
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    let readme =
+        std::fs::read_to_string(project_path(&temp, "test-owner").join("README.md")).unwrap();
+    assert!(
+        readme.contains("https://github.com/acme/test-owner/actions/workflows/test.yml/badge.svg")
+    );
+
+    // Without an owner there is no repository to link
+    let result = run_boots_command(&["boots", "lib", "test-no-repo"], temp.path());
+    assert!(result.success, "Generation failed: {}", result.stderr);
+    let cargo =
+        std::fs::read_to_string(project_path(&temp, "test-no-repo").join("Cargo.toml")).unwrap();
+    assert!(!cargo.contains("repository"));
+}
+
+// Option Tests
+
+#[test]
+fn test_conflicting_and_inapplicable_options() {
+    let temp = TempProject::new();
+    for (args, message) in [
+        (
+            [
+                "boots",
+                "service",
+                "test-opts",
+                "--options",
+                "postgres,sqlite",
+            ],
+            "'sqlite' conflicts with 'postgres'",
+        ),
+        (
+            ["boots", "cli", "test-opts", "--options", "grpc"],
+            "'grpc' does not apply to cli projects",
+        ),
+        (
+            ["boots", "service", "test-opts", "--options", "no-http"],
+            "'no-http' needs 'grpc'",
+        ),
+    ] {
+        let result = run_boots_command(&args, temp.path());
+        assert!(!result.success, "{:?} should be rejected", args);
+        assert!(
+            result.stderr.contains(message),
+            "unexpected error for {:?}: {}",
+            args,
+            result.stderr
+        );
+    }
+    assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_options_help_lists_options_for_the_type() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "cli", "--help"], temp.path());
+    assert!(result.success);
+    assert!(
+        result
+            .stdout
+            .contains("client       HTTP client module with reqwest")
+    );
+    assert!(
+        result
+            .stdout
+            .contains("no-<option> turns off: persistence, client")
+    );
+    assert!(!result.stdout.contains("grpc"));
+}
+
+// List Tests
+
+#[test]
+fn test_list_matches_option_model() {
+    let temp = TempProject::new();
+    let result = run_boots_command(
+        &["boots", "list", "options", "service", "--json"],
+        temp.path(),
+    );
+    assert!(result.success, "list failed: {}", result.stderr);
+    let options: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    let names: Vec<&str> = options
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"file") && names.contains(&"ssr"));
+    assert!(!names.contains(&"client"));
+    let spa = options
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["name"] == "spa");
+    assert_eq!(
+        spa.unwrap()["aliases"],
+        serde_json::json!(["fe:spa", "fe-spa"])
+    );
+
+    let result = run_boots_command(&["boots", "list", "--json", "modules"], temp.path());
+    let modules: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    let client = modules
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == "client");
+    assert_eq!(client.unwrap()["optional"], serde_json::json!(["cli"]));
+
+    let result = run_boots_command(&["boots", "list", "types"], temp.path());
+    assert!(result.stdout.lines().any(|l| l.starts_with("lib ")));
+
+    let result = run_boots_command(&["boots", "list", "templates"], temp.path());
+    assert!(
+        result
+            .stdout
+            .lines()
+            .any(|l| l.starts_with("Cargo.toml") && l.contains("base/Cargo.workspace.toml"))
+    );
+}
+
+// Completion and Man Page Tests
+
+#[test]
+fn test_completions_script_and_options_values() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["completions", "bash"], temp.path());
+    assert!(result.success, "completions failed: {}", result.stderr);
+    assert!(result.stdout.contains("-F _clap_complete_boots boots"));
+    assert!(
+        result.stdout.contains("cargo-boots"),
+        "calls back into this executable"
+    );
+
+    // Through cargo, the script completes `cargo boots ...`
+    let result = run_boots_command(&["boots", "completions", "bash"], temp.path());
+    assert!(result.success, "completions failed: {}", result.stderr);
+    assert!(
+        result
+            .stdout
+            .contains("-F _clap_complete_cargo_boots cargo")
+    );
+    let result = run_boots_command(&["boots", "completions", "--help"], temp.path());
+    assert!(result.stdout.contains("replaces cargo's own completions"));
+
+    // What the script asks when completing `boots service my-api --options postgres,g`
+    let result = run_boots_command_with_env(
+        &[
+            "--",
+            "boots",
+            "service",
+            "my-api",
+            "--options",
+            "postgres,g",
+        ],
+        temp.path(),
+        &[
+            ("COMPLETE", "bash"),
+            ("_CLAP_COMPLETE_INDEX", "4"),
+            ("_CLAP_COMPLETE_COMP_TYPE", "9"),
+            ("_CLAP_COMPLETE_SPACE", "true"),
+            ("_CLAP_IFS", "\n"),
+        ],
+    );
+    assert!(result.success, "completion failed: {}", result.stderr);
+    assert_eq!(result.stdout.trim(), "postgres,grpc");
+
+    let result = run_boots_command_with_env(
+        &[
+            "--",
+            "cargo",
+            "boots",
+            "service",
+            "my-api",
+            "--options",
+            "postgres,g",
+        ],
+        temp.path(),
+        &[
+            ("COMPLETE", "bash"),
+            ("_CLAP_COMPLETE_INDEX", "5"),
+            ("_CLAP_COMPLETE_COMP_TYPE", "9"),
+            ("_CLAP_COMPLETE_SPACE", "true"),
+            ("_CLAP_IFS", "\n"),
+        ],
+    );
+    assert!(result.success, "completion failed: {}", result.stderr);
+    assert_eq!(result.stdout.trim(), "postgres,grpc");
+
+    let result = run_boots_command_with_env(
+        &["--", "boots", "cli", "my-tool", "--options", ""],
+        temp.path(),
+        &[("COMPLETE", "fish")],
+    );
+    assert!(
+        result
+            .stdout
+            .contains("client\tHTTP client module with reqwest")
+    );
+    assert!(!result.stdout.contains("grpc"));
+}
+
+#[test]
+fn test_man_page() {
+    let temp = TempProject::new();
+    let result = run_boots_command(&["boots", "man"], temp.path());
+    assert!(result.success, "man failed: {}", result.stderr);
+    assert!(result.stdout.contains(".TH cargo-boots 1"));
+    assert!(result.stdout.contains("cargo boots"));
+
+    let result = run_boots_command(&["boots", "man", "--help"], temp.path());
+    assert!(result.stdout.contains("Write cargo-boots.1 and a page"));
+    let result = run_boots_command(&["man", "--help"], temp.path());
+    assert!(result.stdout.contains("Write boots.1 and a page"));
+
+    let dir = temp.path().join("man");
+    let result = run_boots_command(
+        &["boots", "man", "--out-dir", dir.to_str().unwrap()],
+        temp.path(),
     );
+    assert!(result.success, "man failed: {}", result.stderr);
+    assert!(dir.join("cargo-boots.1").is_file());
+    let service = std::fs::read_to_string(dir.join("cargo-boots-service.1")).unwrap();
+    assert!(service.contains("postgres"));
 }
@@ -0,0 +1,320 @@
+use super::name::validate_name;
+use super::options::parse_option_list;
+use super::types::*;
+use super::user::{UserConfig, normalize_repository};
+use crate::error::{BootsError, Result};
+use std::fs;
+use std::path::Path;
+
+/// Format of a `--config` file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(ConfigFormat::Toml),
+            "json" => Some(ConfigFormat::Json),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Read a project description such as
+///
+/// ```toml
+/// name = "my-api"
+/// type = "service"
+/// persistence = "postgres"
+/// grpc = true
+///
+/// [modules.api]
+/// port = 8080
+/// ```
+///
+/// from a TOML, JSON or YAML file. The author, repository and license default to those of
+/// `defaults`. Sample projects are fixed like `--options sample`: postgres, the spa and
+/// HTTP, so setting anything else is an error.
+pub fn load_config(path: &Path, defaults: &UserConfig) -> Result<ProjectConfig> {
+    let invalid = |message: String| BootsError::ConfigFile {
+        path: path.display().to_string(),
+        message,
+    };
+    let format = ConfigFormat::from_path(path)
+        .ok_or_else(|| invalid("expected a .toml, .json, .yaml or .yml file".to_string()))?;
+    let text = fs::read_to_string(path)?;
+    parse_config(&text, format, defaults).map_err(|e| match e {
+        BootsError::InvalidOption(message) => invalid(message),
+        e => e,
+    })
+}
+
+/// Parse a project description in `format`, filling in `defaults`; see `load_config`
+pub fn parse_config(
+    text: &str,
+    format: ConfigFormat,
+    defaults: &UserConfig,
+) -> Result<ProjectConfig> {
+    let parsed = match format {
+        ConfigFormat::Toml => toml_edit::de::from_str(text).map_err(|e| e.to_string()),
+        ConfigFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_norway::from_str(text).map_err(|e| e.to_string()),
+    };
+    let mut config: ProjectConfig =
+        parsed.map_err(|e| BootsError::InvalidOption(e.trim().to_string()))?;
+    validate_name(&config.name)?;
+
+    // The same checks as `--options`, so e.g. `grpc = true` is rejected for cli projects
+    parse_option_list(config.project_type, &config.options().join(","))?;
+    if config.project_type == ProjectType::Sample {
+        check_sample(&config)?;
+        config.persistence = Some(PersistenceType::Postgres);
+        config.frontend = Some(FrontendType::Spa);
+    }
+    let modules = config.modules();
+    if let Some(module) = config
+        .module_settings
+        .keys()
+        .find(|module| !modules.contains(module))
+    {
+        return Err(BootsError::InvalidOption(format!(
+            "[modules.{}] has settings for a module this {} project does not have",
+            module, config.project_type
+        )));
+    }
+
+    if config.author_name.is_empty() && config.author_email.is_empty() {
+        (config.author_name, config.author_email) = defaults.author_parts();
+    }
+    if config.repository.is_empty() {
+        config.repository = defaults.repository_for(&config.name);
+    } else {
+        config.repository = normalize_repository(&config.repository);
+    }
+    if config.license.is_empty() {
+        config.license = defaults
+            .license
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LICENSE.to_string());
+    }
+    Ok(config)
+}
+
+/// `config.options()` is only `sample` for samples, so check each setting against it
+fn check_sample(config: &ProjectConfig) -> Result<()> {
+    let changed = [
+        (
+            "persistence",
+            config
+                .persistence
+                .is_some_and(|p| p != PersistenceType::Postgres),
+        ),
+        (
+            "frontend",
+            config.frontend.is_some_and(|f| f != FrontendType::Spa),
+        ),
+        ("grpc", config.has_grpc),
+        ("http", !config.has_http),
+        ("client", config.has_client),
+    ];
+    match changed.iter().find(|(_, changed)| *changed) {
+        Some((key, _)) => Err(BootsError::InvalidOption(format!(
+            "'{}' cannot be changed for sample projects, which use postgres, spa and HTTP",
+            key
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_options_with;
+
+    fn defaults() -> UserConfig {
+        UserConfig {
+            author: Some("Git <git@example.com>".to_string()),
+            license: Some("ISC".to_string()),
+            ..UserConfig::default()
+        }
+    }
+
+    fn service(config: &ProjectConfig) {
+        assert_eq!(config.name, "my-api");
+        assert_eq!(config.project_type, ProjectType::Service);
+        assert_eq!(config.persistence, Some(PersistenceType::Postgres));
+        assert!(config.has_grpc);
+        assert!(config.has_http);
+        assert!(!config.has_client);
+        assert_eq!(config.author_name, "Jane");
+        assert_eq!(config.license, "Apache-2.0");
+    }
+
+    #[test]
+    fn test_parse_all_formats() {
+        let toml = r#"
+name = "my-api"
+type = "service"
+persistence = "postgres"
+grpc = true
+author_name = "Jane"
+license = "Apache-2.0"
+"#;
+        let json = r#"{"name": "my-api", "type": "service", "persistence": "postgres",
+            "grpc": true, "author_name": "Jane", "license": "Apache-2.0"}"#;
+        let yaml = "name: my-api\ntype: service\npersistence: postgres\ngrpc: true\n\
+            author_name: Jane\nlicense: Apache-2.0\n";
+
+        service(&parse_config(toml, ConfigFormat::Toml, &defaults()).unwrap());
+        service(&parse_config(json, ConfigFormat::Json, &defaults()).unwrap());
+        service(&parse_config(yaml, ConfigFormat::Yaml, &defaults()).unwrap());
+    }
+
+    #[test]
+    fn test_matches_parse_options() {
+        let text = "name = \"my-tool\"\ntype = \"cli\"\nclient = true\nauthor_name = \"x\"\n";
+        let config = parse_config(text, ConfigFormat::Toml, &defaults()).unwrap();
+        let parsed =
+            parse_options_with(ProjectType::Cli, "my-tool", Some("client"), &defaults()).unwrap();
+        assert_eq!(
+            config,
+            ProjectConfig {
+                author_name: "x".to_string(),
+                author_email: String::new(),
+                ..parsed
+            }
+        );
+    }
+
+    #[test]
+    fn test_inapplicable_settings() {
+        let text = "name = \"my-tool\"\ntype = \"cli\"\ngrpc = true\n";
+        let error = parse_config(text, ConfigFormat::Toml, &defaults()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("'grpc' does not apply to cli projects")
+        );
+
+        // Without HTTP a service needs gRPC
+        let text = "name: my-api\ntype: service\nhttp: false\n";
+        assert!(parse_config(text, ConfigFormat::Yaml, &defaults()).is_err());
+        let text = "name: my-api\ntype: service\nhttp: false\ngrpc: true\n";
+        assert!(
+            !parse_config(text, ConfigFormat::Yaml, &defaults())
+                .unwrap()
+                .has_http
+        );
+    }
+
+    #[test]
+    fn test_module_settings() {
+        let toml = "name = \"my-api\"\ntype = \"service\"\n\n[modules.api]\nport = 8080\n\
+                    cors = true\n\n[modules.runtime]\nlog = \"debug\"\n";
+        let yaml = "name: my-api\ntype: service\nmodules:\n  api:\n    port: 8080\n    cors: true\n  \
+                    runtime:\n    log: debug\n";
+        let config = parse_config(toml, ConfigFormat::Toml, &defaults()).unwrap();
+        assert_eq!(
+            config.module_settings[&Module::Api]["port"],
+            SettingValue::Integer(8080)
+        );
+        assert_eq!(
+            config.module_settings[&Module::Api]["cors"],
+            SettingValue::Bool(true)
+        );
+        assert_eq!(
+            config.module_settings[&Module::Runtime]["log"].to_string(),
+            "debug"
+        );
+        assert_eq!(
+            parse_config(yaml, ConfigFormat::Yaml, &defaults()).unwrap(),
+            config
+        );
+
+        let text = "name = \"my-tool\"\ntype = \"cli\"\n\n[modules.api]\nport = 8080\n";
+        let error = parse_config(text, ConfigFormat::Toml, &defaults()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("[modules.api] has settings for a module")
+        );
+        let text = "name = \"my-tool\"\ntype = \"cli\"\n\n[modules.web]\nport = 8080\n";
+        assert!(parse_config(text, ConfigFormat::Toml, &defaults()).is_err());
+    }
+
+    #[test]
+    fn test_sample_defaults() {
+        let config = parse_config(
+            "{\"name\": \"board\", \"type\": \"sample\"}",
+            ConfigFormat::Json,
+            &defaults(),
+        )
+        .unwrap();
+        assert_eq!(config.options(), vec!["sample"]);
+        assert_eq!(config.author_email, "git@example.com");
+        assert_eq!(config.license, "ISC");
+        assert_eq!(config.persistence, Some(PersistenceType::Postgres));
+        assert_eq!(config.frontend, Some(FrontendType::Spa));
+
+        // Restating the defaults is fine, anything else is what `sample,sqlite` would be
+        let text = "name = \"board\"\ntype = \"sample\"\npersistence = \"postgres\"\n";
+        assert!(parse_config(text, ConfigFormat::Toml, &defaults()).is_ok());
+        for setting in [
+            "persistence = \"sqlite\"",
+            "frontend = \"ssr\"",
+            "grpc = true",
+            "http = false",
+            "client = true",
+        ] {
+            let text = format!("name = \"board\"\ntype = \"sample\"\n{}\n", setting);
+            let error = parse_config(&text, ConfigFormat::Toml, &defaults()).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("cannot be changed for sample projects"),
+                "{}: {}",
+                setting,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        let err = |text: &str| parse_config(text, ConfigFormat::Toml, &defaults()).unwrap_err();
+
+        assert!(matches!(
+            err("name = \"1api\"\ntype = \"service\"\n"),
+            BootsError::InvalidName { .. }
+        ));
+        assert!(
+            err("name = \"api\"\ntype = \"service\"\npersistence = \"mysql\"\n")
+                .to_string()
+                .contains("unknown variant `mysql`")
+        );
+        assert!(
+            err("name = \"api\"\ntype = \"service\"\ngrcp = true\n")
+                .to_string()
+                .contains("unknown field `grcp`")
+        );
+        assert!(
+            err("name = \"api\"\n")
+                .to_string()
+                .contains("missing field `type`")
+        );
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("specs/api.yml")),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("api.ini")), None);
+    }
+}
@@ -1,5 +1,16 @@
+pub mod file;
+pub mod name;
+pub mod options;
 pub mod parser;
 pub mod types;
+pub mod user;
 
-pub use parser::parse_options;
-pub use types::{FrontendType, Module, PersistenceType, ProjectConfig, ProjectType};
+pub use file::{ConfigFormat, load_config, parse_config};
+pub use name::validate_name;
+pub use options::{OPTIONS, OptionKind, OptionSpec, complete_options, options_for};
+pub use parser::{parse_options, parse_options_with};
+pub use types::{
+    DEFAULT_LICENSE, FrontendType, Module, PersistenceType, ProjectConfig, ProjectType,
+    SettingValue,
+};
+pub use user::UserConfig;
@@ -0,0 +1,136 @@
+use crate::error::{BootsError, Result};
+
+/// Rust keywords, strict and reserved; Cargo refuses them as package names
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Names that clash with the standard crates, Cargo's build directories or Windows devices
+const RESERVED: &[&str] = &[
+    "alloc",
+    "core",
+    "proc-macro",
+    "proc_macro",
+    "std",
+    "test",
+    "build",
+    "deps",
+    "examples",
+    "incremental",
+    "aux",
+    "con",
+    "nul",
+    "prn",
+];
+
+/// Check `name` can prefix Cargo package names (`<name>-core`): lowercase ASCII letters,
+/// digits, `-` and `_`, starting with a letter, and not a keyword or reserved name
+pub fn validate_name(name: &str) -> Result<()> {
+    let invalid = |reason: &str| BootsError::InvalidName {
+        name: name.to_string(),
+        reason: reason.to_string(),
+        suggestion: suggest_name(name),
+    };
+
+    if name.is_empty() {
+        return Err(invalid("the name is empty"));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(invalid(&format!(
+            "'{}' is not allowed, use letters, digits, '-' and '_'",
+            c
+        )));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(invalid("the name must start with a letter"));
+    }
+    if name.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err(invalid("the name must be lowercase"));
+    }
+    if KEYWORDS.contains(&name) {
+        return Err(invalid("the name is a Rust keyword"));
+    }
+    if RESERVED.contains(&name) {
+        return Err(invalid("the name is reserved"));
+    }
+    Ok(())
+}
+
+/// Closest valid name: lowercase kebab-case without leading digits, `-app` appended to
+/// keywords and reserved names
+fn suggest_name(name: &str) -> Option<String> {
+    let mut kebab = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            // Split camelCase words
+            if c.is_ascii_uppercase()
+                && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                kebab.push('-');
+            }
+            kebab.push(c.to_ascii_lowercase());
+        } else if !kebab.ends_with('-') {
+            kebab.push('-');
+        }
+        previous = Some(c);
+    }
+
+    let kebab = kebab
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '-')
+        .trim_end_matches('-');
+    let suggestion = if KEYWORDS.contains(&kebab) || RESERVED.contains(&kebab) {
+        format!("{}-app", kebab)
+    } else {
+        kebab.to_string()
+    };
+    (!suggestion.is_empty() && suggestion != name).then_some(suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(name: &str) -> Option<String> {
+        match validate_name(name) {
+            Err(BootsError::InvalidName { suggestion, .. }) => suggestion,
+            other => panic!("expected InvalidName for {:?}, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn test_valid_names() {
+        for name in ["my-api", "user_service", "a", "api2", "test-svc"] {
+            assert!(validate_name(name).is_ok(), "{} should be valid", name);
+        }
+    }
+
+    #[test]
+    fn test_invalid_names_with_suggestions() {
+        assert_eq!(suggestion("1api").as_deref(), Some("api"));
+        assert_eq!(suggestion("My.Lib").as_deref(), Some("my-lib"));
+        assert_eq!(suggestion("my api").as_deref(), Some("my-api"));
+        assert_eq!(suggestion("UserService").as_deref(), Some("user-service"));
+        assert_eq!(suggestion("test").as_deref(), Some("test-app"));
+        assert_eq!(suggestion("std").as_deref(), Some("std-app"));
+        assert_eq!(suggestion("fn").as_deref(), Some("fn-app"));
+        assert_eq!(suggestion(""), None);
+        assert_eq!(suggestion("123"), None);
+    }
+
+    #[test]
+    fn test_error_message() {
+        let err = validate_name("My.Lib").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid project name 'My.Lib': '.' is not allowed, use letters, digits, '-' and '_' (try 'my-lib')"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use super::types::{FrontendType, PersistenceType, ProjectType};
+use crate::error::{BootsError, Result};
+
+/// What an `--options` entry sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    /// A persistence backend; `None` is the preferred one from the user config
+    Persistence(Option<PersistenceType>),
+    /// A frontend; `None` is the preferred one from the user config
+    Frontend(Option<FrontendType>),
+    Grpc,
+    Http,
+    Client,
+    Sample,
+}
+
+impl OptionKind {
+    /// Options in the same group conflict when they ask for different things
+    pub fn group(&self) -> &'static str {
+        match self {
+            OptionKind::Persistence(_) => "persistence",
+            OptionKind::Frontend(_) => "frontend",
+            OptionKind::Grpc => "grpc",
+            OptionKind::Http => "http",
+            OptionKind::Client => "client",
+            OptionKind::Sample => "sample",
+        }
+    }
+}
+
+/// An option accepted by `--options`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    /// Other spellings, kept for older command lines
+    pub aliases: &'static [&'static str],
+    pub kind: OptionKind,
+    pub project_types: &'static [ProjectType],
+    /// Accepts a `no-<name>` form that turns the feature off
+    pub negatable: bool,
+    pub description: &'static str,
+}
+
+impl OptionSpec {
+    pub fn applies_to(&self, project_type: ProjectType) -> bool {
+        self.project_types.contains(&project_type)
+    }
+}
+
+const SERVICE_AND_CLI: &[ProjectType] = &[ProjectType::Service, ProjectType::Cli];
+const SERVICE: &[ProjectType] = &[ProjectType::Service];
+
+/// Every `--options` entry
+pub const OPTIONS: &[OptionSpec] = &[
+    OptionSpec {
+        name: "postgres",
+        aliases: &[],
+        kind: OptionKind::Persistence(Some(PersistenceType::Postgres)),
+        project_types: SERVICE_AND_CLI,
+        negatable: false,
+        description: "PostgreSQL persistence with sqlx and migrations",
+    },
+    OptionSpec {
+        name: "sqlite",
+        aliases: &[],
+        kind: OptionKind::Persistence(Some(PersistenceType::Sqlite)),
+        project_types: SERVICE_AND_CLI,
+        negatable: false,
+        description: "SQLite persistence with sqlx",
+    },
+    OptionSpec {
+        name: "file",
+        aliases: &[],
+        kind: OptionKind::Persistence(Some(PersistenceType::File)),
+        project_types: SERVICE_AND_CLI,
+        negatable: false,
+        description: "Local file-based persistence",
+    },
+    OptionSpec {
+        name: "persistence",
+        aliases: &[],
+        kind: OptionKind::Persistence(None),
+        project_types: SERVICE_AND_CLI,
+        negatable: true,
+        description: "Persistence with the preferred backend (file unless configured)",
+    },
+    OptionSpec {
+        name: "grpc",
+        aliases: &[],
+        kind: OptionKind::Grpc,
+        project_types: SERVICE,
+        negatable: true,
+        description: "gRPC support with tonic and a proto directory",
+    },
+    OptionSpec {
+        name: "http",
+        aliases: &[],
+        kind: OptionKind::Http,
+        project_types: SERVICE,
+        negatable: true,
+        description: "HTTP API with /health and /metrics (on unless no-http, which needs grpc)",
+    },
+    OptionSpec {
+        name: "client",
+        aliases: &[],
+        kind: OptionKind::Client,
+        project_types: &[ProjectType::Cli],
+        negatable: true,
+        description: "HTTP client module with reqwest",
+    },
+    OptionSpec {
+        name: "spa",
+        aliases: &["fe:spa", "fe-spa"],
+        kind: OptionKind::Frontend(Some(FrontendType::Spa)),
+        project_types: SERVICE,
+        negatable: false,
+        description: "React + Vite frontend served by Nginx",
+    },
+    OptionSpec {
+        name: "ssr",
+        aliases: &["fe:ssr", "fe-ssr"],
+        kind: OptionKind::Frontend(Some(FrontendType::Ssr)),
+        project_types: SERVICE,
+        negatable: false,
+        description: "Next.js frontend with the App Router",
+    },
+    OptionSpec {
+        name: "frontend",
+        aliases: &[],
+        kind: OptionKind::Frontend(None),
+        project_types: SERVICE,
+        negatable: true,
+        description: "Frontend of the preferred type (spa unless configured)",
+    },
+    OptionSpec {
+        name: "sample",
+        aliases: &[],
+        kind: OptionKind::Sample,
+        project_types: &[ProjectType::Sample],
+        negatable: false,
+        description: "Full board application with PostgreSQL and a React SPA",
+    },
+];
+
+/// Options that apply to `project_type`, in help order
+pub fn options_for(project_type: ProjectType) -> Vec<&'static OptionSpec> {
+    OPTIONS
+        .iter()
+        .filter(|spec| spec.applies_to(project_type))
+        .collect()
+}
+
+/// A parsed `--options` entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedOption {
+    pub spec: &'static OptionSpec,
+    /// Written as `no-<name>`
+    pub negated: bool,
+}
+
+impl ParsedOption {
+    /// How the option was written
+    pub fn token(&self) -> String {
+        if self.negated {
+            format!("no-{}", self.spec.name)
+        } else {
+            self.spec.name.to_string()
+        }
+    }
+
+    /// `true` if `self` and `other` ask for incompatible things. A bare `persistence` or
+    /// `frontend` goes with any specific choice of the same group.
+    fn conflicts_with(&self, other: &ParsedOption) -> bool {
+        if self.spec.kind.group() != other.spec.kind.group() {
+            return false;
+        }
+        if self.negated || other.negated {
+            return self.negated != other.negated;
+        }
+        match (self.spec.kind, other.spec.kind) {
+            (OptionKind::Persistence(Some(a)), OptionKind::Persistence(Some(b))) => a != b,
+            (OptionKind::Frontend(Some(a)), OptionKind::Frontend(Some(b))) => a != b,
+            _ => false,
+        }
+    }
+}
+
+/// Parse comma-separated `options` for `project_type`, rejecting unknown, inapplicable
+/// and conflicting entries
+pub fn parse_option_list(project_type: ProjectType, options: &str) -> Result<Vec<ParsedOption>> {
+    let parsed = parse_entries(project_type, options)?;
+
+    // Without HTTP, gRPC is all a service serves, and there is no API for a frontend
+    let enabled = |group: &str| {
+        parsed
+            .iter()
+            .find(|p| p.spec.kind.group() == group && !p.negated)
+    };
+    if parsed
+        .iter()
+        .any(|p| p.spec.kind == OptionKind::Http && p.negated)
+    {
+        if enabled("grpc").is_none() {
+            return Err(BootsError::InvalidOption(
+                "'no-http' needs 'grpc', or the service serves nothing".to_string(),
+            ));
+        }
+        if let Some(frontend) = enabled("frontend") {
+            return Err(BootsError::InvalidOption(format!(
+                "'no-http' leaves '{}' without an API, choose one",
+                frontend.token()
+            )));
+        }
+    }
+    Ok(parsed)
+}
+
+/// Each entry of `options` on its own and against the ones before it
+fn parse_entries(project_type: ProjectType, options: &str) -> Result<Vec<ParsedOption>> {
+    let invalid = |message: String| Err(BootsError::InvalidOption(message));
+    let available = || {
+        let names: Vec<&str> = options_for(project_type).iter().map(|s| s.name).collect();
+        match names.as_slice() {
+            [] => format!("{} projects take no options", project_type),
+            names => format!("{} projects take: {}", project_type, names.join(", ")),
+        }
+    };
+
+    let mut parsed: Vec<ParsedOption> = Vec::new();
+    for token in options.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (name, negated) = match token.strip_prefix("no-") {
+            Some(name) => (name, true),
+            None => (token, false),
+        };
+        let Some(spec) = OPTIONS
+            .iter()
+            .find(|spec| spec.name == name || spec.aliases.contains(&name))
+        else {
+            return invalid(format!("'{}' is not an option; {}", token, available()));
+        };
+
+        if !spec.applies_to(project_type) {
+            return invalid(format!(
+                "'{}' does not apply to {} projects; {}",
+                spec.name,
+                project_type,
+                available()
+            ));
+        }
+        if negated && !spec.negatable {
+            return invalid(
+                match OPTIONS
+                    .iter()
+                    .find(|s| s.negatable && s.kind.group() == spec.kind.group())
+                {
+                    Some(negatable) => {
+                        format!("'{}' cannot be negated, use 'no-{}'", token, negatable.name)
+                    }
+                    None => format!("'{}' cannot be negated", token),
+                },
+            );
+        }
+
+        let option = ParsedOption { spec, negated };
+        if let Some(other) = parsed.iter().find(|p| p.conflicts_with(&option)) {
+            return invalid(format!(
+                "'{}' conflicts with '{}', choose one {}",
+                token,
+                other.token(),
+                spec.kind.group()
+            ));
+        }
+        if !parsed.contains(&option) {
+            parsed.push(option);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Completions for a partly typed `--options` value: `current` with its last entry replaced
+/// by each option that can follow the ones before it, with a description. Options another
+/// one needs (e.g. `grpc` for `no-http`) may still follow.
+pub fn complete_options(project_type: ProjectType, current: &str) -> Vec<(String, String)> {
+    let (done, partial) = match current.rsplit_once(',') {
+        Some((done, partial)) => (format!("{},", done), partial),
+        None => (String::new(), current),
+    };
+    let typed: Vec<&str> = done.split(',').map(str::trim).collect();
+
+    let mut candidates = Vec::new();
+    for spec in options_for(project_type) {
+        let mut tokens = vec![(spec.name.to_string(), spec.description.to_string())];
+        if spec.negatable {
+            tokens.push((
+                format!("no-{}", spec.name),
+                format!("Turn off {}", spec.name),
+            ));
+        }
+        for (token, description) in tokens {
+            let value = format!("{}{}", done, token);
+            if token.starts_with(partial)
+                && !typed.contains(&token.as_str())
+                && parse_entries(project_type, &value).is_ok()
+            {
+                candidates.push((value, description));
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(project_type: ProjectType, options: &str) -> String {
+        parse_option_list(project_type, options)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_aliases_and_negation() {
+        let parsed = parse_option_list(ProjectType::Service, "fe:ssr, no-grpc,,").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].spec.name, "ssr");
+        assert_eq!(parsed[1].token(), "no-grpc");
+    }
+
+    #[test]
+    fn test_conflicts() {
+        assert_eq!(
+            error(ProjectType::Service, "postgres,sqlite"),
+            "Invalid option: 'sqlite' conflicts with 'postgres', choose one persistence"
+        );
+        assert!(error(ProjectType::Service, "grpc,no-grpc").contains("conflicts with 'grpc'"));
+        assert!(error(ProjectType::Cli, "no-persistence,file").contains("conflicts"));
+        assert!(error(ProjectType::Service, "spa,fe:ssr").contains("conflicts with 'spa'"));
+
+        // A bare group option goes with a specific choice, repeats are harmless
+        assert!(parse_option_list(ProjectType::Cli, "sqlite,persistence,sqlite").is_ok());
+        assert!(parse_option_list(ProjectType::Service, "frontend,ssr").is_ok());
+    }
+
+    #[test]
+    fn test_inapplicable_and_unknown() {
+        assert_eq!(
+            error(ProjectType::Cli, "grpc"),
+            "Invalid option: 'grpc' does not apply to cli projects; cli projects take: \
+             postgres, sqlite, file, persistence, client"
+        );
+        assert!(error(ProjectType::Lib, "client").contains("lib projects take no options"));
+        assert!(error(ProjectType::Service, "sample,grpc").contains("does not apply"));
+        assert!(
+            error(ProjectType::Service, "mysql")
+                .starts_with("Invalid option: 'mysql' is not an option")
+        );
+        assert!(error(ProjectType::Service, "no-http").contains("needs 'grpc'"));
+        assert!(error(ProjectType::Service, "grpc,no-http,spa").contains("without an API"));
+        assert!(parse_option_list(ProjectType::Service, "no-http,grpc").is_ok());
+        assert!(error(ProjectType::Service, "no-postgres").contains("use 'no-persistence'"));
+    }
+
+    #[test]
+    fn test_complete_options() {
+        let values = |current| -> Vec<String> {
+            complete_options(ProjectType::Service, current)
+                .into_iter()
+                .map(|(value, _)| value)
+                .collect()
+        };
+        assert_eq!(values("s"), vec!["sqlite", "spa", "ssr"]);
+        assert_eq!(values("postgres,f"), vec!["postgres,frontend"]);
+        assert_eq!(
+            values("grpc,no-"),
+            vec!["grpc,no-persistence", "grpc,no-http", "grpc,no-frontend"]
+        );
+        assert!(complete_options(ProjectType::Lib, "").is_empty());
+    }
+
+    #[test]
+    fn test_every_type_has_its_options() {
+        assert_eq!(options_for(ProjectType::Lib).len(), 0);
+        assert_eq!(
+            options_for(ProjectType::Sample)
+                .iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>(),
+            vec!["sample"]
+        );
+        for spec in OPTIONS {
+            assert!(
+                !spec.project_types.is_empty(),
+                "{} applies nowhere",
+                spec.name
+            );
+        }
+    }
+}
@@ -1,9 +1,13 @@
+use super::name::validate_name;
+use super::options::{OptionKind, parse_option_list};
 use super::types::*;
-use crate::error::{BootsError, Result};
+use super::user::UserConfig;
+use crate::error::Result;
+use std::collections::BTreeMap;
 use std::process::Command;
 
 /// Read git config value, returning empty string if not found or on error
-fn get_git_config(key: &str) -> String {
+pub(crate) fn get_git_config(key: &str) -> String {
     Command::new("git")
         .args(["config", "--get", key])
         .output()
@@ -19,13 +23,37 @@ fn get_git_config(key: &str) -> String {
         .unwrap_or_default()
 }
 
+/// Parse `--options` without user defaults: no author or repository, the default license
+/// and the file and spa backends for a bare `persistence` or `frontend`
 pub fn parse_options(
     project_type: ProjectType,
     name: &str,
     options: Option<&str>,
 ) -> Result<ProjectConfig> {
-    let author_name = get_git_config("user.name");
-    let author_email = get_git_config("user.email");
+    parse_options_with(project_type, name, options, &UserConfig::default())
+}
+
+/// Parse `--options`, taking the author, repository, license and preferred backends from
+/// `defaults`
+pub fn parse_options_with(
+    project_type: ProjectType,
+    name: &str,
+    options: Option<&str>,
+    defaults: &UserConfig,
+) -> Result<ProjectConfig> {
+    validate_name(name)?;
+    build_config(project_type, name, options, defaults)
+}
+
+/// `parse_options_with` without checking `name`, for projects that already exist under
+/// it and may predate the current naming rules
+pub(crate) fn build_config(
+    project_type: ProjectType,
+    name: &str,
+    options: Option<&str>,
+    defaults: &UserConfig,
+) -> Result<ProjectConfig> {
+    let (author_name, author_email) = defaults.author_parts();
 
     let mut config = ProjectConfig {
         name: name.to_string(),
@@ -37,49 +65,43 @@ pub fn parse_options(
         has_client: false,
         author_name,
         author_email,
+        repository: defaults.repository_for(name),
+        license: defaults
+            .license
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LICENSE.to_string()),
+        module_settings: BTreeMap::new(),
     };
 
-    if let Some(opts) = options {
-        let opts_list: Vec<&str> = opts.split(',').map(|s| s.trim()).collect();
-
-        // If 'sample' option is present, ignore all other options
-        if opts_list.contains(&"sample") {
-            let other_options: Vec<&str> = opts_list
-                .iter()
-                .filter(|o| **o != "sample")
-                .copied()
-                .collect();
-            if !other_options.is_empty() {
-                eprintln!(
-                    "Warning: 'sample' option ignores other options: {:?}",
-                    other_options
-                );
-            }
-            // Sample project uses postgres and spa by default
-            config.persistence = Some(PersistenceType::Postgres);
-            config.frontend = Some(FrontendType::Spa);
-            return Ok(config);
-        }
+    // Sample projects use postgres and spa by default
+    if project_type == ProjectType::Sample {
+        config.persistence = Some(PersistenceType::Postgres);
+        config.frontend = Some(FrontendType::Spa);
+    }
 
-        for option in opts_list {
-            match option {
-                "postgres" => config.persistence = Some(PersistenceType::Postgres),
-                "sqlite" => config.persistence = Some(PersistenceType::Sqlite),
-                "file" => config.persistence = Some(PersistenceType::File),
-                "grpc" => config.has_grpc = true,
-                "http" => config.has_http = true,
-                "client" => config.has_client = true,
-                "persistence" => {
-                    if config.persistence.is_none() {
-                        config.persistence = Some(PersistenceType::File);
-                    }
-                }
-                // Frontend options: fe:spa or fe:ssr
-                "fe:spa" | "fe-spa" | "spa" => config.frontend = Some(FrontendType::Spa),
-                "fe:ssr" | "fe-ssr" | "ssr" => config.frontend = Some(FrontendType::Ssr),
-                "" => continue,
-                _ => return Err(BootsError::InvalidOption(option.to_string())),
+    for option in parse_option_list(project_type, options.unwrap_or_default())? {
+        let enabled = !option.negated;
+        match option.spec.kind {
+            OptionKind::Persistence(backend) => {
+                config.persistence = enabled.then(|| {
+                    backend
+                        .or(config.persistence)
+                        .or(defaults.persistence)
+                        .unwrap_or(PersistenceType::File)
+                })
             }
+            OptionKind::Frontend(frontend) => {
+                config.frontend = enabled.then(|| {
+                    frontend
+                        .or(config.frontend)
+                        .or(defaults.frontend)
+                        .unwrap_or(FrontendType::Spa)
+                })
+            }
+            OptionKind::Grpc => config.has_grpc = enabled,
+            OptionKind::Http => config.has_http = enabled,
+            OptionKind::Client => config.has_client = enabled,
+            OptionKind::Sample => {}
         }
     }
 
@@ -154,16 +176,102 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_sample_ignores_other_options() {
-        // sample should ignore postgres,grpc and use default sample config
-        let config = parse_options(
+    fn test_parse_sample_rejects_other_options() {
+        let result = parse_options(
             ProjectType::Sample,
             "test-board",
             Some("sample,grpc,sqlite"),
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("'grpc' does not apply")
+        );
+    }
+
+    #[test]
+    fn test_parse_conflicting_options() {
+        let result = parse_options(ProjectType::Service, "test-svc", Some("postgres,sqlite"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("conflicts with 'postgres'")
+        );
+
+        let result = parse_options(ProjectType::Cli, "test-cli", Some("grpc"));
+        assert!(result.is_err(), "grpc does nothing for cli projects");
+    }
+
+    #[test]
+    fn test_parse_negated_options() {
+        let defaults = UserConfig {
+            persistence: Some(PersistenceType::Sqlite),
+            ..UserConfig::default()
+        };
+        let config = parse_options_with(
+            ProjectType::Service,
+            "test-svc",
+            Some("no-grpc,no-persistence,no-frontend"),
+            &defaults,
         )
         .unwrap();
-        assert_eq!(config.persistence, Some(PersistenceType::Postgres)); // not sqlite
-        assert!(!config.has_grpc); // grpc ignored
-        assert_eq!(config.frontend, Some(FrontendType::Spa));
+        assert!(config.persistence.is_none());
+        assert!(config.frontend.is_none());
+        assert!(!config.has_grpc);
+
+        let config = parse_options(ProjectType::Service, "test-svc", Some("grpc,no-http")).unwrap();
+        assert!(config.has_grpc && !config.has_http);
+        assert_eq!(config.options(), vec!["grpc", "no-http"]);
+    }
+
+    #[test]
+    fn test_parse_with_user_defaults() {
+        let defaults = UserConfig {
+            author: Some("Jane <jane@example.com>".to_string()),
+            owner: Some("acme".to_string()),
+            license: Some("Apache-2.0".to_string()),
+            persistence: Some(PersistenceType::Sqlite),
+            frontend: Some(FrontendType::Ssr),
+            ..UserConfig::default()
+        };
+        let config = parse_options_with(
+            ProjectType::Service,
+            "test-svc",
+            Some("persistence,frontend"),
+            &defaults,
+        )
+        .unwrap();
+        assert_eq!(config.persistence, Some(PersistenceType::Sqlite));
+        assert_eq!(config.frontend, Some(FrontendType::Ssr));
+        assert_eq!(config.author_name, "Jane");
+        assert_eq!(config.author_email, "jane@example.com");
+        assert_eq!(config.repository, "https://github.com/acme/test-svc");
+        assert_eq!(config.license, "Apache-2.0");
+
+        // An explicit backend wins over the preferred one
+        let config = parse_options_with(
+            ProjectType::Cli,
+            "test-cli",
+            Some("file,persistence"),
+            &defaults,
+        )
+        .unwrap();
+        assert_eq!(config.persistence, Some(PersistenceType::File));
+    }
+
+    #[test]
+    fn test_options_round_trip() {
+        for (project_type, options) in [
+            (ProjectType::Service, "sqlite,grpc,ssr"),
+            (ProjectType::Cli, "client,file"),
+            (ProjectType::Sample, "sample"),
+        ] {
+            let config = parse_options(project_type, "demo", Some(options)).unwrap();
+            let again =
+                parse_options(project_type, "demo", Some(&config.options().join(","))).unwrap();
+            assert_eq!(again, config);
+        }
     }
 }
@@ -1,4 +1,11 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::error::BootsError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProjectType {
     Service,
     Cli,
@@ -6,14 +13,118 @@ pub enum ProjectType {
     Sample,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl ProjectType {
+    pub const ALL: [ProjectType; 4] = [
+        ProjectType::Service,
+        ProjectType::Cli,
+        ProjectType::Lib,
+        ProjectType::Sample,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectType::Service => "service",
+            ProjectType::Cli => "cli",
+            ProjectType::Lib => "lib",
+            ProjectType::Sample => "sample",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ProjectType::Service => "Full-stack service with API, runtime server, CLI and core",
+            ProjectType::Cli => "CLI application with core and optional client and persistence",
+            ProjectType::Lib => "Library crate with examples",
+            ProjectType::Sample => "Board application with RBAC, PostgreSQL and a React SPA",
+        }
+    }
+
+    /// Modules every project of this type has
+    pub fn required_modules(&self) -> Vec<Module> {
+        self.config(false).modules()
+    }
+
+    /// Modules `--options` can add to projects of this type
+    pub fn optional_modules(&self) -> Vec<Module> {
+        let required = self.required_modules();
+        self.config(true)
+            .modules()
+            .into_iter()
+            .filter(|m| !required.contains(m))
+            .collect()
+    }
+
+    /// A config of this type with every optional feature on or off
+    fn config(&self, all: bool) -> ProjectConfig {
+        ProjectConfig {
+            name: String::new(),
+            project_type: *self,
+            persistence: all.then_some(PersistenceType::File),
+            frontend: all.then_some(FrontendType::Spa),
+            has_grpc: all,
+            has_http: true,
+            has_client: all,
+            author_name: String::new(),
+            author_email: String::new(),
+            repository: String::new(),
+            license: String::new(),
+            module_settings: BTreeMap::new(),
+        }
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProjectType {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| BootsError::InvalidProjectType(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PersistenceType {
     Postgres,
     Sqlite,
     File,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl PersistenceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PersistenceType::Postgres => "postgres",
+            PersistenceType::Sqlite => "sqlite",
+            PersistenceType::File => "file",
+        }
+    }
+}
+
+impl FromStr for PersistenceType {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            PersistenceType::Postgres,
+            PersistenceType::Sqlite,
+            PersistenceType::File,
+        ]
+        .into_iter()
+        .find(|p| p.as_str() == s)
+        .ok_or_else(|| BootsError::InvalidOption(format!("unknown persistence: {}", s)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FrontendType {
     /// SPA: React + Vite, served by Nginx
     Spa,
@@ -21,7 +132,28 @@ pub enum FrontendType {
     Ssr,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl FrontendType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrontendType::Spa => "spa",
+            FrontendType::Ssr => "ssr",
+        }
+    }
+}
+
+impl FromStr for FrontendType {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [FrontendType::Spa, FrontendType::Ssr]
+            .into_iter()
+            .find(|f| f.as_str() == s)
+            .ok_or_else(|| BootsError::InvalidOption(format!("unknown frontend: {}", s)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Module {
     Core,
     Api,
@@ -31,17 +163,118 @@ pub enum Module {
     Persistence,
 }
 
-#[derive(Debug, Clone)]
+impl Module {
+    pub const ALL: [Module; 6] = [
+        Module::Core,
+        Module::Api,
+        Module::Runtime,
+        Module::Cli,
+        Module::Client,
+        Module::Persistence,
+    ];
+
+    /// Crate directory name under `crates/`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Module::Core => "core",
+            Module::Api => "api",
+            Module::Runtime => "runtime",
+            Module::Cli => "cli",
+            Module::Client => "client",
+            Module::Persistence => "persistence",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Module::Core => "Business logic and domain types",
+            Module::Api => "HTTP/gRPC handlers and routes",
+            Module::Runtime => "Server startup and configuration",
+            Module::Cli => "Command-line interface",
+            Module::Client => "HTTP client for external APIs",
+            Module::Persistence => "Database access layer",
+        }
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Module {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| BootsError::InvalidOption(format!("unknown module: {}", s)))
+    }
+}
+
+/// License of generated projects unless configured otherwise
+pub const DEFAULT_LICENSE: &str = "MIT";
+
+/// Everything a project is generated from; (de)serialized with the keys of the
+/// `[project]` table in `.boots.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ProjectConfig {
     pub name: String,
+    #[serde(rename = "type")]
     pub project_type: ProjectType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub persistence: Option<PersistenceType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub frontend: Option<FrontendType>,
+    #[serde(rename = "grpc", default)]
     pub has_grpc: bool,
+    #[serde(rename = "http", default = "enabled")]
     pub has_http: bool,
+    #[serde(rename = "client", default)]
     pub has_client: bool,
+    #[serde(default)]
     pub author_name: String,
+    #[serde(default)]
     pub author_email: String,
+    /// URL of the project's repository, empty if unknown
+    #[serde(default)]
+    pub repository: String,
+    #[serde(default)]
+    pub license: String,
+    /// Settings for the templates of a module, e.g. `[modules.api]`; templates read them
+    /// as `{{settings.api.<key>}}`
+    #[serde(
+        rename = "modules",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub module_settings: BTreeMap<Module, BTreeMap<String, SettingValue>>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Value of a module setting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SettingValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+}
+
+impl fmt::Display for SettingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingValue::Bool(value) => write!(f, "{}", value),
+            SettingValue::Integer(value) => write!(f, "{}", value),
+            SettingValue::String(value) => f.write_str(value),
+        }
+    }
 }
 
 impl ProjectConfig {
@@ -77,4 +310,51 @@ impl ProjectConfig {
             }
         }
     }
+
+    /// `--options` that make `parse_options` produce this config again
+    pub fn options(&self) -> Vec<&'static str> {
+        if self.project_type == ProjectType::Sample {
+            return vec!["sample"];
+        }
+        let mut options = Vec::new();
+        if let Some(persistence) = self.persistence {
+            options.push(persistence.as_str());
+        }
+        if self.has_grpc {
+            options.push("grpc");
+        }
+        if !self.has_http {
+            options.push("no-http");
+        }
+        if self.has_client {
+            options.push("client");
+        }
+        if let Some(frontend) = self.frontend {
+            options.push(frontend.as_str());
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_and_optional_modules() {
+        assert_eq!(
+            ProjectType::Cli.required_modules(),
+            vec![Module::Core, Module::Cli]
+        );
+        assert_eq!(
+            ProjectType::Cli.optional_modules(),
+            vec![Module::Client, Module::Persistence]
+        );
+        assert_eq!(
+            ProjectType::Service.optional_modules(),
+            vec![Module::Persistence]
+        );
+        assert!(ProjectType::Lib.optional_modules().is_empty());
+        assert!(ProjectType::Sample.optional_modules().is_empty());
+    }
 }
@@ -0,0 +1,407 @@
+use super::parser::get_git_config;
+use super::types::{FrontendType, PersistenceType};
+use crate::error::{BootsError, Result};
+use crate::generator::Hook;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, value};
+
+/// Git host of repository URLs built from an owner
+pub const DEFAULT_REPO_HOST: &str = "https://github.com";
+
+/// Defaults for every project a user generates, read from `~/.config/boots/config.toml`
+/// and, for keys missing there, from `BOOTS_<KEY>` environment variables and git config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// `Name <email>`, as in Cargo's `authors`
+    pub author: Option<String>,
+    /// User or organization the projects are published under
+    pub owner: Option<String>,
+    /// Base URL of the git host, `https://github.com` unless set, e.g. a GitLab or Gitea
+    /// server
+    pub repo_host: Option<String>,
+    pub license: Option<String>,
+    /// Backend for a bare `persistence` option and the default answer of `boots new`
+    pub persistence: Option<PersistenceType>,
+    /// Frontend for a bare `frontend` option and the default answer of `boots new`
+    pub frontend: Option<FrontendType>,
+    /// Used when `--template-pack` is not given
+    pub template_pack: Option<PathBuf>,
+    /// Built-in hooks run when no hook flag is given: git, lock, fmt, commit
+    pub hooks: Option<Vec<String>>,
+}
+
+impl UserConfig {
+    pub const KEYS: [&str; 8] = [
+        "author",
+        "owner",
+        "repo_host",
+        "license",
+        "persistence",
+        "frontend",
+        "template_pack",
+        "hooks",
+    ];
+
+    /// `$XDG_CONFIG_HOME/boots/config.toml`, or `~/.config/boots/config.toml`
+    pub fn path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME")
+                    .or_else(|| env::var_os("USERPROFILE"))
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })?;
+        Some(config_home.join("boots").join("config.toml"))
+    }
+
+    /// The user config file, empty if there is none
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config: Self = toml_edit::de::from_str(&fs::read_to_string(path)?).map_err(|e| {
+            BootsError::ConfigFile {
+                path: path.display().to_string(),
+                message: e.to_string().trim().to_string(),
+            }
+        })?;
+        for hook in config.hooks.iter().flatten() {
+            hook.parse::<Hook>().map_err(|e| BootsError::ConfigFile {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Settings from the `BOOTS_AUTHOR`, `BOOTS_OWNER`, ... environment variables
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        for key in Self::KEYS {
+            let var = format!("BOOTS_{}", key.to_uppercase());
+            if let Ok(value) = env::var(&var) {
+                config
+                    .set(key, &value)
+                    .map_err(|e| BootsError::InvalidOption(format!("{}: {}", var, e)))?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// The author from git's `user.name` and `user.email`
+    pub fn from_git() -> Self {
+        let name = get_git_config("user.name");
+        let email = get_git_config("user.email");
+        Self {
+            author: join_author(&name, &email),
+            ..Self::default()
+        }
+    }
+
+    /// Effective defaults: the config file, then environment variables, then git config
+    pub fn resolve() -> Result<Self> {
+        let config = Self::load()?.or(Self::from_env()?);
+        if config.author.is_some() {
+            return Ok(config);
+        }
+        Ok(config.or(Self::from_git()))
+    }
+
+    /// Keys set here win, the others come from `fallback`
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            author: self.author.or(fallback.author),
+            owner: self.owner.or(fallback.owner),
+            repo_host: self.repo_host.or(fallback.repo_host),
+            license: self.license.or(fallback.license),
+            persistence: self.persistence.or(fallback.persistence),
+            frontend: self.frontend.or(fallback.frontend),
+            template_pack: self.template_pack.or(fallback.template_pack),
+            hooks: self.hooks.or(fallback.hooks),
+        }
+    }
+
+    /// `(name, email)` of the author, empty when unset
+    pub fn author_parts(&self) -> (String, String) {
+        self.author.as_deref().map(split_author).unwrap_or_default()
+    }
+
+    /// `<repo_host>/<owner>/<name>`, empty without an owner
+    pub fn repository_for(&self, name: &str) -> String {
+        let Some(owner) = &self.owner else {
+            return String::new();
+        };
+        let host = self.repo_host.as_deref().unwrap_or(DEFAULT_REPO_HOST);
+        normalize_repository(&format!(
+            "{}/{}/{}",
+            host.trim_end_matches('/'),
+            owner,
+            name
+        ))
+    }
+
+    /// Built-in hooks to run when no hook flag is given
+    pub fn hooks(&self) -> Vec<Hook> {
+        self.hooks
+            .iter()
+            .flatten()
+            .filter_map(|hook| hook.parse().ok())
+            .collect()
+    }
+
+    /// Value of `key` as `boots config get` prints it, `None` if unset
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "author" => self.author.clone(),
+            "owner" => self.owner.clone(),
+            "repo_host" => self.repo_host.clone(),
+            "license" => self.license.clone(),
+            "persistence" => self.persistence.map(|p| p.as_str().to_string()),
+            "frontend" => self.frontend.map(|f| f.as_str().to_string()),
+            "template_pack" => self.template_pack.as_ref().map(|p| p.display().to_string()),
+            "hooks" => self.hooks.as_ref().map(|hooks| hooks.join(",")),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    /// Set `key` from its string form; an empty value unsets it. Hooks are comma-separated.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        let text = (!value.is_empty()).then(|| value.to_string());
+        match key {
+            "author" => self.author = text,
+            "owner" => self.owner = text,
+            "repo_host" => {
+                if let Some(host) = &text
+                    && !(host.starts_with("https://") || host.starts_with("http://"))
+                {
+                    return Err(BootsError::InvalidOption(format!(
+                        "repo_host must be an http(s) URL such as https://gitlab.com, not '{}'",
+                        host
+                    )));
+                }
+                self.repo_host = text;
+            }
+            "license" => self.license = text,
+            "persistence" => self.persistence = text.map(|p| p.parse()).transpose()?,
+            "frontend" => self.frontend = text.map(|f| f.parse()).transpose()?,
+            "template_pack" => self.template_pack = text.map(PathBuf::from),
+            "hooks" => {
+                let hooks: Vec<String> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|hook| !hook.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                for hook in &hooks {
+                    hook.parse::<Hook>()?;
+                }
+                self.hooks = (!hooks.is_empty()).then_some(hooks);
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// Keys that are set, with their values
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        Self::KEYS
+            .into_iter()
+            .filter_map(|key| Some((key, self.get(key).ok()??)))
+            .collect()
+    }
+
+    /// Write `key` to the config file at `path`, keeping its other keys and comments
+    pub fn save_key(&self, path: &Path, key: &str) -> Result<()> {
+        let text = if path.exists() {
+            fs::read_to_string(path)?
+        } else {
+            String::new()
+        };
+        let mut doc: DocumentMut = text.parse().map_err(|e| BootsError::ConfigFile {
+            path: path.display().to_string(),
+            message: format!("{}", e).trim().to_string(),
+        })?;
+
+        match (key, self.get(key)?) {
+            (_, None) => {
+                doc.remove(key);
+            }
+            ("hooks", Some(_)) => {
+                let hooks: Array = self.hooks.iter().flatten().map(String::as_str).collect();
+                doc[key] = value(hooks);
+            }
+            (_, Some(text)) => doc[key] = value(text),
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, doc.to_string())?;
+        Ok(())
+    }
+}
+
+/// Repository URL without a trailing `/` or `.git`, as used in badges and links
+pub fn normalize_repository(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    url.strip_suffix(".git").unwrap_or(url).to_string()
+}
+
+fn unknown_key(key: &str) -> BootsError {
+    BootsError::InvalidOption(format!(
+        "unknown config key '{}', expected one of: {}",
+        key,
+        UserConfig::KEYS.join(", ")
+    ))
+}
+
+/// `Name <email>` from its parts, `None` if both are empty
+pub(crate) fn join_author(name: &str, email: &str) -> Option<String> {
+    match (name.is_empty(), email.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(name.to_string()),
+        (true, false) => Some(format!("<{}>", email)),
+        (false, false) => Some(format!("{} <{}>", name, email)),
+    }
+}
+
+/// Split `Name <email>` into its parts; either may be missing
+pub(crate) fn split_author(author: &str) -> (String, String) {
+    match author.split_once('<') {
+        Some((name, email)) => (
+            name.trim().to_string(),
+            email.trim().trim_end_matches('>').to_string(),
+        ),
+        None => (author.trim().to_string(), String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_get_and_validation() {
+        let mut config = UserConfig::default();
+        config.set("author", "Jane Doe <jane@example.com>").unwrap();
+        config.set("persistence", "sqlite").unwrap();
+        config.set("hooks", "git, fmt").unwrap();
+
+        assert_eq!(
+            config.author_parts(),
+            ("Jane Doe".to_string(), "jane@example.com".to_string())
+        );
+        assert_eq!(
+            config.get("persistence").unwrap().as_deref(),
+            Some("sqlite")
+        );
+        assert_eq!(config.hooks(), vec![Hook::Git, Hook::Fmt]);
+        assert_eq!(config.get("owner").unwrap(), None);
+
+        assert!(config.set("persistence", "mysql").is_err());
+        assert!(config.set("hooks", "git,deploy").is_err());
+        assert!(config.set("colour", "blue").is_err());
+
+        config.set("persistence", "").unwrap();
+        assert_eq!(config.persistence, None);
+    }
+
+    #[test]
+    fn test_layers() {
+        let user = UserConfig {
+            license: Some("Apache-2.0".to_string()),
+            ..UserConfig::default()
+        };
+        let env = UserConfig {
+            author: Some("Env <env@example.com>".to_string()),
+            license: Some("MIT".to_string()),
+            ..UserConfig::default()
+        };
+        let config = user.or(env);
+        assert_eq!(config.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(config.author.as_deref(), Some("Env <env@example.com>"));
+    }
+
+    #[test]
+    fn test_save_key_keeps_comments() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("boots/config.toml");
+
+        let mut config = UserConfig::default();
+        config.set("owner", "acme").unwrap();
+        config.save_key(&path, "owner").unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("# my defaults\n{}", text)).unwrap();
+
+        config.set("hooks", "git,lock").unwrap();
+        config.save_key(&path, "hooks").unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# my defaults\nowner = \"acme\"\n"));
+        assert!(text.contains("hooks = [\"git\", \"lock\"]"));
+        assert_eq!(UserConfig::load_from(&path).unwrap(), config);
+
+        config.set("owner", "").unwrap();
+        config.save_key(&path, "owner").unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("owner"));
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(&path, "hooks = [\"deploy\"]\n").unwrap();
+        assert!(matches!(
+            UserConfig::load_from(&path),
+            Err(BootsError::ConfigFile { .. })
+        ));
+        fs::write(&path, "autor = \"x\"\n").unwrap();
+        assert!(
+            UserConfig::load_from(&path)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown field `autor`")
+        );
+    }
+
+    #[test]
+    fn test_repository_for() {
+        let mut config = UserConfig::default();
+        assert_eq!(config.repository_for("api"), "");
+
+        config.set("owner", "acme").unwrap();
+        assert_eq!(config.repository_for("api"), "https://github.com/acme/api");
+
+        config.set("repo_host", "https://git.example.com/").unwrap();
+        assert_eq!(
+            config.repository_for("api"),
+            "https://git.example.com/acme/api"
+        );
+        assert!(config.set("repo_host", "gitlab.com").is_err());
+        assert_eq!(
+            normalize_repository("https://gitlab.com/acme/api.git"),
+            "https://gitlab.com/acme/api"
+        );
+    }
+
+    #[test]
+    fn test_split_author() {
+        assert_eq!(split_author("Jane"), ("Jane".to_string(), String::new()));
+        assert_eq!(split_author("<j@x>"), (String::new(), "j@x".to_string()));
+        assert_eq!(join_author("Jane", "j@x").as_deref(), Some("Jane <j@x>"));
+        assert_eq!(join_author("", ""), None);
+    }
+}
@@ -5,17 +5,42 @@ pub enum BootsError {
     #[error("Invalid project type: {0}")]
     InvalidProjectType(String),
 
+    #[error(
+        "Invalid project name '{name}': {reason}{}",
+        suggestion.as_ref().map(|s| format!(" (try '{}')", s)).unwrap_or_default()
+    )]
+    InvalidName {
+        name: String,
+        reason: String,
+        suggestion: Option<String>,
+    },
+
     #[error("Invalid option: {0}")]
     InvalidOption(String),
 
+    #[error("Invalid config file {path}: {message}")]
+    ConfigFile { path: String, message: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Template error: {0}")]
     Template(String),
 
+    #[error("Template pack error: {0}")]
+    TemplatePack(String),
+
+    #[error("Workspace error: {0}")]
+    Workspace(String),
+
     #[error("Directory already exists: {0}")]
     DirectoryExists(String),
+
+    #[error("Files already exist: {}", .0.join(", "))]
+    FilesExist(Vec<String>),
+
+    #[error("Hook '{hook}' failed: {message}")]
+    Hook { hook: String, message: String },
 }
 
 pub type Result<T> = std::result::Result<T, BootsError>;
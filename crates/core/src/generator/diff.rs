@@ -0,0 +1,160 @@
+use super::merge::matches;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Lines of unchanged context around each hunk
+const CONTEXT: usize = 3;
+
+/// How a project file differs from a fresh generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    Modified,
+    /// Generated before but removed from the project
+    Deleted,
+    /// Not part of the project when it was generated; boots would create it now
+    Added,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    pub status: DiffStatus,
+    /// Unified diff from the generated file to the project file
+    pub diff: String,
+}
+
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff of `old` and `new` with `CONTEXT` lines of context; empty if they are equal
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // Edit script as (op, old line, new line)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let pairs = matches(&old, &new)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, j)| Some((i, j?)));
+    for (next_i, next_j) in pairs.chain([(old.len(), new.len())]) {
+        ops.extend((i..next_i).map(|i| (Op::Delete, i, j)));
+        ops.extend((j..next_j).map(|j| (Op::Insert, next_i, j)));
+        if next_i < old.len() {
+            ops.push((Op::Equal, next_i, next_j));
+        }
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _, _))| !matches!(op, Op::Equal))
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    let mut start = 0;
+    while start < changes.len() {
+        // Changes closer than two contexts apart share a hunk
+        let mut end = start;
+        while end + 1 < changes.len() && changes[end + 1] - changes[end] <= 2 * CONTEXT {
+            end += 1;
+        }
+        let first = changes[start].saturating_sub(CONTEXT);
+        let last = (changes[end] + CONTEXT + 1).min(ops.len());
+        let hunk = &ops[first..last];
+
+        let old_count = hunk
+            .iter()
+            .filter(|(op, _, _)| !matches!(op, Op::Insert))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|(op, _, _)| !matches!(op, Op::Delete))
+            .count();
+        let (_, old_start, new_start) = hunk[0];
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        );
+
+        for (op, i, j) in hunk {
+            let (prefix, line) = match op {
+                Op::Equal => (' ', old[*i]),
+                Op::Delete => ('-', old[*i]),
+                Op::Insert => ('+', new[*j]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        start = end + 1;
+    }
+    out
+}
+
+/// `start,count` of a hunk, 1-based; an empty range names the line before it
+fn range(start: usize, count: usize) -> String {
+    let start = if count == 0 { start } else { start + 1 };
+    match count {
+        1 => start.to_string(),
+        _ => format!("{},{}", start, count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_is_empty() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "a/x", "b/x"), "");
+    }
+
+    #[test]
+    fn test_single_change_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        assert_eq!(
+            unified_diff(old, new, "a/x", "b/x"),
+            "--- a/x\n+++ b/x\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let new: String = (1..=20)
+            .filter(|n| *n != 19)
+            .map(|n| match n {
+                2 => "two\n".to_string(),
+                n => format!("{}\n", n),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "a/x", "b/x");
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n"));
+        assert!(diff.contains("@@ -16,5 +16,4 @@\n 16\n 17\n 18\n-19\n 20\n"));
+    }
+
+    #[test]
+    fn test_whole_file_removed() {
+        assert_eq!(
+            unified_diff("a\nb", "", "a/x", "/dev/null"),
+            "--- a/x\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-a\n-b\n\\ No newline at end of file\n"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::{BootsError, Result};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+/// A step run in the project directory once it has been generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hook {
+    /// `git init`
+    Git,
+    /// `cargo generate-lockfile`
+    Lock,
+    /// `cargo fmt --all`
+    Fmt,
+    /// Shell command from a template pack's `pack.toml`
+    Shell { pack: String, command: String },
+    /// `git add -A` and a first commit
+    Commit,
+}
+
+impl Hook {
+    /// Commands the hook runs, in order
+    fn commands(&self) -> Vec<Command> {
+        let command = |program: &str, args: &[&str]| {
+            let mut command = Command::new(program);
+            command.args(args);
+            command
+        };
+        match self {
+            Hook::Git => vec![command("git", &["init", "-q"])],
+            Hook::Lock => vec![command("cargo", &["generate-lockfile", "-q"])],
+            Hook::Fmt => vec![command("cargo", &["fmt", "--all"])],
+            Hook::Shell { command: line, .. } if cfg!(windows) => {
+                vec![command("cmd", &["/C", line])]
+            }
+            Hook::Shell { command: line, .. } => vec![command("sh", &["-c", line])],
+            Hook::Commit => vec![
+                command("git", &["add", "-A"]),
+                command("git", &["commit", "-q", "-m", "Initial commit from boots"]),
+            ],
+        }
+    }
+
+    /// Run the hook in `dir`, failing with `BootsError::Hook` on a non-zero exit
+    pub fn run(&self, dir: &Path) -> Result<()> {
+        for mut command in self.commands() {
+            let output = command
+                .current_dir(dir)
+                .output()
+                .map_err(|e| BootsError::Hook {
+                    hook: self.to_string(),
+                    message: e.to_string(),
+                })?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let message = match (stderr.trim(), stdout.trim()) {
+                    ("", "") => output.status.to_string(),
+                    ("", stdout) => format!("{} ({})", stdout, output.status),
+                    (stderr, _) => format!("{} ({})", stderr, output.status),
+                };
+                return Err(BootsError::Hook {
+                    hook: self.to_string(),
+                    message,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hook::Git => f.write_str("git init"),
+            Hook::Lock => f.write_str("cargo generate-lockfile"),
+            Hook::Fmt => f.write_str("cargo fmt"),
+            Hook::Shell { pack, command } => write!(f, "{}: {}", pack, command),
+            Hook::Commit => f.write_str("git commit"),
+        }
+    }
+}
+
+/// Built-in hooks by the name of their command-line flag: git, lock, fmt, commit
+impl FromStr for Hook {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "git" => Ok(Hook::Git),
+            "lock" => Ok(Hook::Lock),
+            "fmt" => Ok(Hook::Fmt),
+            "commit" => Ok(Hook::Commit),
+            _ => Err(BootsError::InvalidOption(format!(
+                "unknown hook '{}', expected git, lock, fmt or commit",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn shell(command: &str) -> Hook {
+        Hook::Shell {
+            pack: "company".to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_shell_hook_runs_in_dir() {
+        let temp = TempDir::new().unwrap();
+        shell("echo hooked > hooked.txt").run(temp.path()).unwrap();
+        assert!(temp.path().join("hooked.txt").exists());
+    }
+
+    #[test]
+    fn test_failed_hook_reports_output() {
+        let temp = TempDir::new().unwrap();
+        let err = shell("echo broken >&2; exit 3")
+            .run(temp.path())
+            .unwrap_err();
+        match err {
+            BootsError::Hook { hook, message } => {
+                assert_eq!(hook, "company: echo broken >&2; exit 3");
+                assert!(message.starts_with("broken"));
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::error::{BootsError, Result};
+use crate::template::TemplateSource;
+use std::str::FromStr;
+use toml_edit::DocumentMut;
+
+/// Path of the manifest in every template layer
+pub const MANIFEST: &str = "manifest.toml";
+
+/// One `[[file]]` entry of `manifest.toml`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    /// Template path, or `None` for an empty file
+    pub template: Option<String>,
+    /// Destination relative to the project root; may contain placeholders
+    pub dest: String,
+    /// Render placeholders, or copy the template verbatim
+    pub render: bool,
+    /// `{{#if}}`-style condition; the file is always written when `None`
+    pub when: Option<String>,
+}
+
+/// The files a generated project is made of, declared in `templates/manifest.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub files: Vec<FileEntry>,
+}
+
+impl Manifest {
+    /// Merge the manifests of every layer of `templates`, so packs and override
+    /// directories can add files or replace entries for the same `dest`
+    pub fn load(templates: &TemplateSource) -> Result<Self> {
+        let mut manifest = Manifest::default();
+        for text in templates.get_all(MANIFEST) {
+            manifest.merge(text.parse()?);
+        }
+        Ok(manifest)
+    }
+
+    /// Entries of `other` replace every entry of `self` with the same `dest`
+    /// (taking the position of the first), or are appended
+    fn merge(&mut self, other: Manifest) {
+        let mut pending = other.files;
+        while !pending.is_empty() {
+            let dest = pending[0].dest.clone();
+            let (group, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|e| e.dest == dest);
+            pending = rest;
+
+            let at = self.files.iter().position(|e| e.dest == dest);
+            self.files.retain(|e| e.dest != dest);
+            let at = at.unwrap_or(self.files.len());
+            self.files.splice(at..at, group);
+        }
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = BootsError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let invalid = |msg: String| BootsError::Template(format!("invalid {}: {}", MANIFEST, msg));
+        let doc: DocumentMut = text.parse().map_err(|e| invalid(format!("{}", e)))?;
+
+        let Some(files) = doc.get("file") else {
+            return Ok(Manifest::default());
+        };
+        let files = files
+            .as_array_of_tables()
+            .ok_or_else(|| invalid("`file` must be an array of tables".to_string()))?;
+
+        files
+            .iter()
+            .enumerate()
+            .map(|(i, table)| {
+                let string =
+                    |key: &str| table.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                Ok(FileEntry {
+                    template: string("template"),
+                    dest: string("dest")
+                        .ok_or_else(|| invalid(format!("file #{} is missing dest", i + 1)))?,
+                    render: table
+                        .get("render")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true),
+                    when: string("when"),
+                })
+            })
+            .collect::<Result<_>>()
+            .map(|files| Manifest { files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::TemplateEngine;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_entries() {
+        let manifest: Manifest = r#"
+[[file]]
+template = "docker/Dockerfile"
+dest = "Dockerfile"
+
+[[file]]
+template = "frontend/spa/nginx.conf"
+dest = "frontend/nginx.conf"
+render = false
+when = 'frontend == "spa"'
+
+[[file]]
+dest = "e2e/fixtures/.gitkeep"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(manifest.files.len(), 3);
+        assert!(manifest.files[0].render);
+        assert!(!manifest.files[1].render);
+        assert_eq!(
+            manifest.files[1].when.as_deref(),
+            Some("frontend == \"spa\"")
+        );
+        assert_eq!(manifest.files[2].template, None);
+    }
+
+    #[test]
+    fn test_missing_dest_error() {
+        let err = "[[file]]\ntemplate = \"a\"\n"
+            .parse::<Manifest>()
+            .unwrap_err();
+        assert!(err.to_string().contains("file #1 is missing dest"));
+    }
+
+    #[test]
+    fn test_builtin_manifest_is_valid() {
+        let manifest = Manifest::load(&TemplateSource::embedded()).unwrap();
+        let templates = TemplateSource::embedded();
+        let engine = TemplateEngine::new();
+
+        for entry in &manifest.files {
+            if let Some(template) = &entry.template {
+                assert!(templates.get(template).is_some(), "missing {}", template);
+            }
+            if let Some(when) = &entry.when {
+                engine.eval(when).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_layers_add_and_replace_entries() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join(MANIFEST),
+            "[[file]]\ntemplate = \"company/CODEOWNERS\"\ndest = \".github/CODEOWNERS\"\n\n\
+             [[file]]\ntemplate = \"company/Dockerfile\"\ndest = \"Dockerfile\"\n\n\
+             [[file]]\ntemplate = \"company/Makefile\"\ndest = \"Makefile\"\n",
+        )
+        .unwrap();
+
+        let builtin = Manifest::load(&TemplateSource::embedded()).unwrap();
+        let templates = TemplateSource::embedded().with_dir(dir.path()).unwrap();
+        let manifest = Manifest::load(&templates).unwrap();
+
+        // Both built-in Makefile variants are replaced by the single override
+        assert_eq!(manifest.files.len(), builtin.files.len());
+        let makefiles: Vec<_> = manifest
+            .files
+            .iter()
+            .filter(|e| e.dest == "Makefile")
+            .collect();
+        assert_eq!(makefiles.len(), 1);
+        assert_eq!(makefiles[0].when, None);
+        let dockerfile = manifest.files.iter().find(|e| e.dest == "Dockerfile");
+        assert_eq!(
+            dockerfile.and_then(|e| e.template.as_deref()),
+            Some("company/Dockerfile")
+        );
+        assert_eq!(manifest.files.last().unwrap().dest, ".github/CODEOWNERS");
+    }
+}
@@ -0,0 +1,161 @@
+/// Result of a line-based three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merged {
+    pub text: String,
+    /// Number of conflict blocks written with `<<<<<<<` markers
+    pub conflicts: usize,
+}
+
+/// Merge the changes from `base` to `theirs` into `ours` (diff3).
+///
+/// Regions changed on only one side take that side; regions changed identically on both
+/// are taken once; anything else becomes a conflict block labelled `ours_label` and
+/// `theirs_label`.
+pub fn merge3(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> Merged {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let in_ours = matches(&base, &ours);
+    let in_theirs = matches(&base, &theirs);
+
+    let mut out = String::new();
+    let mut conflicts = 0;
+    let (mut i, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // Lines unchanged on both sides
+        while i < base.len() && in_ours[i] == Some(a) && in_theirs[i] == Some(b) {
+            out.push_str(base[i]);
+            i += 1;
+            a += 1;
+            b += 1;
+        }
+        if i == base.len() && a == ours.len() && b == theirs.len() {
+            break;
+        }
+
+        // Next base line kept by both sides ends the changed region
+        let (next_i, next_a, next_b) = (i..base.len())
+            .find_map(|k| Some((k, in_ours[k]?, in_theirs[k]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        let base_chunk = &base[i..next_i];
+        let ours_chunk = &ours[a..next_a];
+        let theirs_chunk = &theirs[b..next_b];
+
+        if ours_chunk == base_chunk || ours_chunk == theirs_chunk {
+            theirs_chunk.iter().for_each(|line| out.push_str(line));
+        } else if theirs_chunk == base_chunk {
+            ours_chunk.iter().for_each(|line| out.push_str(line));
+        } else {
+            conflicts += 1;
+            out.push_str(&format!("<<<<<<< {}\n", ours_label));
+            push_lines(&mut out, ours_chunk);
+            out.push_str("=======\n");
+            push_lines(&mut out, theirs_chunk);
+            out.push_str(&format!(">>>>>>> {}\n", theirs_label));
+        }
+
+        (i, a, b) = (next_i, next_a, next_b);
+    }
+
+    Merged {
+        text: out,
+        conflicts,
+    }
+}
+
+/// Lines of a conflict side, making sure the following marker starts on its own line
+fn push_lines(out: &mut String, lines: &[&str]) {
+    lines.iter().for_each(|line| out.push_str(line));
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// For each line of `base`, its index in `other` under a longest common subsequence
+pub(crate) fn matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (base.len(), other.len());
+    // lcs[i][j] = LCS length of base[i..] and other[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if base[i] == other[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            result[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> Merged {
+        merge3(base, ours, theirs, "yours", "boots")
+    }
+
+    #[test]
+    fn test_changes_on_different_lines_merge_cleanly() {
+        let base = "a\nb\nc\nd\n";
+        let ours = "a\nB\nc\nd\n";
+        let theirs = "a\nb\nc\nD\ne\n";
+        let merged = merge(base, ours, theirs);
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "a\nB\nc\nD\ne\n");
+    }
+
+    #[test]
+    fn test_identical_changes_are_taken_once() {
+        let merged = merge("a\nb\n", "a\nx\n", "a\nx\n");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "a\nx\n");
+    }
+
+    #[test]
+    fn test_overlapping_changes_conflict() {
+        let merged = merge("a\nb\nc\n", "a\nmine\nc\n", "a\ntheirs\nc\n");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< yours\nmine\n=======\ntheirs\n>>>>>>> boots\nc\n"
+        );
+    }
+
+    #[test]
+    fn test_unchanged_sides() {
+        assert_eq!(merge("a\n", "a\n", "b\n").text, "b\n");
+        assert_eq!(merge("a\n", "b\n", "a\n").text, "b\n");
+        assert_eq!(merge("", "", "new\n").text, "new\n");
+    }
+
+    #[test]
+    fn test_conflict_without_trailing_newline() {
+        let merged = merge("a", "b", "c");
+        assert_eq!(merged.text, "<<<<<<< yours\nb\n=======\nc\n>>>>>>> boots\n");
+    }
+}
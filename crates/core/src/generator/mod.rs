@@ -1,3 +1,18 @@
+pub mod diff;
+pub mod hooks;
+pub mod manifest;
+pub mod merge;
+pub mod plan;
 pub mod project;
+pub mod project_manifest;
+pub mod upgrade;
+pub mod workspace;
 
+pub use diff::{DiffStatus, FileDiff};
+pub use hooks::Hook;
+pub use manifest::{FileEntry, Manifest};
+pub use plan::{ConflictPolicy, GenerationPlan, PlannedFile};
 pub use project::ProjectGenerator;
+pub use project_manifest::{PROJECT_MANIFEST, ProjectManifest};
+pub use upgrade::{Upgrade, UpgradeStatus, UpgradedFile, generate_with};
+pub use workspace::{RemovedModule, SourceReference, Workspace};
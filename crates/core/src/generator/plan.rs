@@ -0,0 +1,396 @@
+use crate::error::{BootsError, Result};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A file the generator will write, relative to the project root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFile {
+    pub path: PathBuf,
+    /// Template it comes from; `None` for files boots writes itself (`.gitkeep`, `.boots.toml`)
+    pub template: Option<String>,
+    pub content: String,
+}
+
+/// What `GenerationPlan::apply_into` does with planned files that already exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Keep the existing file
+    Skip,
+    /// Replace it with the generated one
+    Overwrite,
+    /// Write nothing at all
+    #[default]
+    Abort,
+}
+
+impl ConflictPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Overwrite => "overwrite",
+            ConflictPolicy::Abort => "abort",
+        }
+    }
+}
+
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = BootsError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "skip" => Ok(ConflictPolicy::Skip),
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            "abort" => Ok(ConflictPolicy::Abort),
+            _ => Err(BootsError::InvalidOption(format!(
+                "unknown conflict policy: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Everything `ProjectGenerator::generate` would create, computed without touching disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationPlan {
+    /// Project directory the files are written under
+    pub root: PathBuf,
+    pub files: Vec<PlannedFile>,
+}
+
+impl GenerationPlan {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Plan of the text files already under `root`, e.g. a project generated by another
+    /// boots; `.git` and files that are not UTF-8 are left out
+    pub fn from_dir(root: impl Into<PathBuf>) -> Result<Self> {
+        let mut plan = Self::new(root);
+        let mut dirs = vec![PathBuf::new()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(plan.root.join(&dir))? {
+                let entry = entry?;
+                let path = dir.join(entry.file_name());
+                if entry.file_type()?.is_dir() {
+                    if entry.file_name() != ".git" {
+                        dirs.push(path);
+                    }
+                } else if let Ok(content) = String::from_utf8(fs::read(entry.path())?) {
+                    plan.add(PlannedFile {
+                        path,
+                        template: None,
+                        content,
+                    });
+                }
+            }
+        }
+        plan.files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(plan)
+    }
+
+    /// Add a file, replacing any earlier file at the same path
+    pub fn add(&mut self, file: PlannedFile) {
+        match self.files.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => *existing = file,
+            None => self.files.push(file),
+        }
+    }
+
+    pub fn file(&self, path: impl AsRef<Path>) -> Option<&PlannedFile> {
+        self.files.iter().find(|f| f.path == path.as_ref())
+    }
+
+    /// Directories that will be created, relative to the root, parents first
+    pub fn directories(&self) -> Vec<PathBuf> {
+        let mut dirs = BTreeSet::new();
+        for file in &self.files {
+            dirs.extend(
+                file.path
+                    .ancestors()
+                    .skip(1)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map(Path::to_path_buf),
+            );
+        }
+        dirs.into_iter().collect()
+    }
+
+    /// Total size of all files in bytes
+    pub fn size(&self) -> usize {
+        self.files.iter().map(|f| f.content.len()).sum()
+    }
+
+    /// Sibling of `root` the project is written to before being renamed into place
+    pub fn staging_dir(&self) -> PathBuf {
+        let name = self
+            .root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.root
+            .with_file_name(format!(".{}.boots-staging-{}", name, std::process::id()))
+    }
+
+    /// Write the planned files to `root`, which must not exist yet.
+    ///
+    /// Files go to `staging_dir()` first, which is renamed to `root` only once everything
+    /// was written, so a failure never leaves a half-generated project behind.
+    pub fn apply(&self) -> Result<()> {
+        if self.root.exists() {
+            return Err(BootsError::DirectoryExists(self.root.display().to_string()));
+        }
+
+        let staging = self.staging_dir();
+        let result = self
+            .write_to(&staging)
+            .and_then(|()| Ok(fs::rename(&staging, &self.root)?));
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    /// Planned files that already exist under `root`
+    pub fn conflicts(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|f| self.root.join(&f.path).exists())
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    /// Write the planned files into `root`, which may already exist and hold files of its
+    /// own, e.g. a freshly cloned repository. Existing files are handled by `policy`;
+    /// returns the ones that were kept.
+    ///
+    /// If writing fails, created files and directories are removed and overwritten files
+    /// are restored.
+    pub fn apply_into(&self, policy: ConflictPolicy) -> Result<Vec<PathBuf>> {
+        let conflicts = self.conflicts();
+        if policy == ConflictPolicy::Abort && !conflicts.is_empty() {
+            return Err(BootsError::FilesExist(
+                conflicts.iter().map(|p| p.display().to_string()).collect(),
+            ));
+        }
+
+        let mut created_dirs = Vec::new();
+        let mut written = Vec::new();
+        if let Err(e) = self.write_into(policy, &conflicts, &mut created_dirs, &mut written) {
+            for (path, previous) in written.iter().rev() {
+                let _ = match previous {
+                    Some(content) => fs::write(path, content),
+                    None => fs::remove_file(path),
+                };
+            }
+            for dir in created_dirs.iter().rev() {
+                let _ = fs::remove_dir(dir);
+            }
+            return Err(e);
+        }
+
+        Ok(match policy {
+            ConflictPolicy::Skip => conflicts,
+            _ => Vec::new(),
+        })
+    }
+
+    /// Records every directory it creates and every file it writes along with the
+    /// content it replaced, so `apply_into` can undo them
+    fn write_into(
+        &self,
+        policy: ConflictPolicy,
+        conflicts: &[PathBuf],
+        created_dirs: &mut Vec<PathBuf>,
+        written: &mut Vec<(PathBuf, Option<Vec<u8>>)>,
+    ) -> Result<()> {
+        let dirs = std::iter::once(PathBuf::new()).chain(self.directories());
+        for dir in dirs.map(|d| self.root.join(d)) {
+            if !dir.exists() {
+                // Parents first, so a missing `root` is created by its own entry
+                for missing in dir.ancestors().collect::<Vec<_>>().into_iter().rev() {
+                    if !missing.as_os_str().is_empty() && !missing.exists() {
+                        fs::create_dir(missing)?;
+                        created_dirs.push(missing.to_path_buf());
+                    }
+                }
+            }
+        }
+
+        for file in &self.files {
+            let exists = conflicts.contains(&file.path);
+            if exists && policy == ConflictPolicy::Skip {
+                continue;
+            }
+            let path = self.root.join(&file.path);
+            let previous = if exists { Some(fs::read(&path)?) } else { None };
+            written.push((path.clone(), previous));
+            fs::write(&path, &file.content)?;
+        }
+        Ok(())
+    }
+
+    fn write_to(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        for sub in self.directories() {
+            fs::create_dir_all(dir.join(sub))?;
+        }
+        for file in &self.files {
+            fs::write(dir.join(&file.path), &file.content)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn planned(path: &str, content: &str) -> PlannedFile {
+        PlannedFile {
+            path: PathBuf::from(path),
+            template: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_directories_include_parents() {
+        let mut plan = GenerationPlan::new("demo");
+        plan.add(planned("Cargo.toml", ""));
+        plan.add(planned("crates/core/src/lib.rs", ""));
+        plan.add(planned(".github/workflows/test.yml", ""));
+
+        let dirs: Vec<String> = plan
+            .directories()
+            .iter()
+            .map(|d| d.display().to_string())
+            .collect();
+        assert_eq!(
+            dirs,
+            [
+                ".github",
+                ".github/workflows",
+                "crates",
+                "crates/core",
+                "crates/core/src"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_replaces_same_path() {
+        let mut plan = GenerationPlan::new("demo");
+        plan.add(planned("Makefile", "a"));
+        plan.add(planned("Makefile", "bb"));
+        assert_eq!(plan.files.len(), 1);
+        assert_eq!(plan.size(), 2);
+    }
+
+    #[test]
+    fn test_apply_writes_files() {
+        let temp = TempDir::new().unwrap();
+        let mut plan = GenerationPlan::new(temp.path().join("demo"));
+        plan.add(planned("crates/core/src/lib.rs", "pub fn f() {}"));
+        plan.add(planned("e2e/fixtures/.gitkeep", ""));
+
+        plan.apply().unwrap();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("demo/crates/core/src/lib.rs")).unwrap(),
+            "pub fn f() {}"
+        );
+        assert!(temp.path().join("demo/e2e/fixtures/.gitkeep").exists());
+
+        assert!(matches!(plan.apply(), Err(BootsError::DirectoryExists(_))));
+    }
+
+    #[test]
+    fn test_from_dir_reads_applied_plan() {
+        let temp = TempDir::new().unwrap();
+        let mut plan = GenerationPlan::new(temp.path().join("demo"));
+        plan.add(planned("crates/core/src/lib.rs", "pub fn f() {}"));
+        plan.add(planned("Cargo.toml", "[workspace]"));
+        plan.apply().unwrap();
+        // Hooks leave a repository and binary files that are not part of the output
+        fs::create_dir_all(plan.root.join(".git/objects")).unwrap();
+        fs::write(plan.root.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(plan.root.join("favicon.ico"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let read = GenerationPlan::from_dir(&plan.root).unwrap();
+        plan.files.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(read, plan);
+    }
+
+    #[test]
+    fn test_apply_into_existing_directory() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("README.md"), "# cloned\n").unwrap();
+        fs::write(temp.path().join("LICENSE"), "MIT\n").unwrap();
+
+        let mut plan = GenerationPlan::new(temp.path());
+        plan.add(planned("README.md", "# generated\n"));
+        plan.add(planned("crates/core/src/lib.rs", "pub fn f() {}"));
+        assert_eq!(plan.conflicts(), [PathBuf::from("README.md")]);
+
+        let err = plan.apply_into(ConflictPolicy::Abort).unwrap_err();
+        assert!(matches!(&err, BootsError::FilesExist(files) if files == &["README.md"]));
+        assert!(!temp.path().join("crates").exists());
+
+        let skipped = plan.apply_into(ConflictPolicy::Skip).unwrap();
+        assert_eq!(skipped, [PathBuf::from("README.md")]);
+        let readme = fs::read_to_string(temp.path().join("README.md")).unwrap();
+        assert_eq!(readme, "# cloned\n");
+        assert!(temp.path().join("crates/core/src/lib.rs").exists());
+
+        assert!(
+            plan.apply_into(ConflictPolicy::Overwrite)
+                .unwrap()
+                .is_empty()
+        );
+        let readme = fs::read_to_string(temp.path().join("README.md")).unwrap();
+        assert_eq!(readme, "# generated\n");
+        assert!(temp.path().join("LICENSE").exists());
+    }
+
+    #[test]
+    fn test_failed_apply_into_restores_directory() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("README.md"), "# cloned\n").unwrap();
+
+        let mut plan = GenerationPlan::new(temp.path());
+        plan.add(planned("README.md", "# generated\n"));
+        plan.add(planned("crates/core/Cargo.toml", "[package]"));
+        plan.add(planned("crates/core/Cargo.toml/oops", ""));
+
+        assert!(plan.apply_into(ConflictPolicy::Overwrite).is_err());
+        let readme = fs::read_to_string(temp.path().join("README.md")).unwrap();
+        assert_eq!(readme, "# cloned\n");
+        assert!(!temp.path().join("crates").exists());
+    }
+
+    #[test]
+    fn test_failed_apply_leaves_nothing_behind() {
+        let temp = TempDir::new().unwrap();
+        let mut plan = GenerationPlan::new(temp.path().join("demo"));
+        plan.add(planned("Cargo.toml", "[workspace]"));
+        // Creating `Cargo.toml/` as a directory makes writing `Cargo.toml` fail
+        plan.add(planned("Cargo.toml/oops", ""));
+
+        assert!(plan.apply().is_err());
+        assert!(!plan.root.exists());
+        assert!(!plan.staging_dir().exists());
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+}
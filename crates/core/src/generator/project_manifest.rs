@@ -0,0 +1,341 @@
+use crate::config::{Module, ProjectConfig};
+use crate::error::{BootsError, Result};
+use crate::template::{TemplatePack, TemplateSource};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, value};
+
+/// File at the root of every generated project
+pub const PROJECT_MANIFEST: &str = ".boots.toml";
+
+/// How a project was generated: boots version, settings, template packs and a hash
+/// of every generated file, written to `.boots.toml`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectManifest {
+    pub boots_version: String,
+    pub config: ProjectConfig,
+    pub modules: Vec<Module>,
+    /// Each template pack, highest priority first
+    pub packs: Vec<RecordedPack>,
+    /// `sha256:<hex>` of each generated file, keyed by `/`-separated relative path
+    pub files: BTreeMap<String, String>,
+}
+
+impl ProjectManifest {
+    pub fn new(config: &ProjectConfig, packs: &[&TemplatePack]) -> Self {
+        Self {
+            boots_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: config.clone(),
+            modules: config.modules(),
+            packs: packs.iter().map(|pack| RecordedPack::new(pack)).collect(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Record the hash of a generated file
+    pub fn record(&mut self, path: &Path, content: &str) {
+        self.files
+            .insert(manifest_path(path), content_hash(content));
+    }
+
+    /// Drop the hash of the file at `path`, or of every file under it for a directory
+    pub fn forget(&mut self, path: &Path) {
+        let path = manifest_path(path);
+        let dir = format!("{}/", path);
+        self.files
+            .retain(|file, _| *file != path && !file.starts_with(&dir));
+    }
+
+    /// `true` if boots generated a file at `path`
+    pub fn is_recorded(&self, path: &Path) -> bool {
+        self.files.contains_key(&manifest_path(path))
+    }
+
+    /// `true` if `content` is what boots generated at `path`
+    pub fn is_unmodified(&self, path: &Path, content: &str) -> bool {
+        self.files.get(&manifest_path(path)) == Some(&content_hash(content))
+    }
+
+    /// The templates the project was generated from: the built-ins with the recorded packs
+    /// loaded again from where they were
+    pub fn templates(&self) -> Result<TemplateSource> {
+        self.packs
+            .iter()
+            .rev()
+            .try_fold(TemplateSource::embedded(), |templates, pack| {
+                Ok(templates.with_pack(pack.load()?))
+            })
+    }
+
+    /// The manifest of the project at `root`, or `None` if it has none
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(PROJECT_MANIFEST);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(path)?.parse().map(Some)
+    }
+}
+
+/// A template pack as recorded in `.boots.toml`: what it was and where it came from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RecordedPack {
+    pub name: String,
+    pub version: String,
+    /// Directory, archive or git checkout the pack was loaded from
+    pub path: PathBuf,
+    /// The git ref it was read at, if any
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+}
+
+impl RecordedPack {
+    pub fn new(pack: &TemplatePack) -> Self {
+        Self {
+            name: pack.manifest.name.clone(),
+            version: pack.manifest.version.clone(),
+            path: pack.location.clone(),
+            git_ref: pack.git_ref.clone(),
+        }
+    }
+
+    /// Load the pack again, failing if what is there now is another version
+    pub fn load(&self) -> Result<TemplatePack> {
+        let pack = TemplatePack::load(&self.path, self.git_ref.as_deref())?;
+        if pack.manifest.name != self.name || pack.manifest.version != self.version {
+            return Err(BootsError::TemplatePack(format!(
+                "{} is now {} {}, the project was generated with {} {}",
+                self.path.display(),
+                pack.manifest.name,
+                pack.manifest.version,
+                self.name,
+                self.version
+            )));
+        }
+        Ok(pack)
+    }
+
+    fn to_inline(&self) -> InlineTable {
+        let mut pack = InlineTable::new();
+        pack.insert("name", self.name.as_str().into());
+        pack.insert("version", self.version.as_str().into());
+        pack.insert("path", self.path.to_string_lossy().as_ref().into());
+        if let Some(git_ref) = &self.git_ref {
+            pack.insert("ref", git_ref.as_str().into());
+        }
+        pack
+    }
+}
+
+pub fn content_hash(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+fn manifest_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl fmt::Display for ProjectManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let config = &self.config;
+        let mut doc = DocumentMut::new();
+
+        let mut boots = Table::new();
+        boots.insert("version", value(&self.boots_version));
+        doc.insert("boots", Item::Table(boots));
+
+        let project = toml_edit::ser::to_document(config)
+            .map_err(|_| fmt::Error)?
+            .as_table()
+            .clone();
+        doc.insert("project", Item::Table(project));
+
+        let mut workspace = Table::new();
+        workspace.insert(
+            "modules",
+            value(self.modules.iter().map(|m| m.as_str()).collect::<Array>()),
+        );
+        doc.insert("workspace", Item::Table(workspace));
+
+        if !self.packs.is_empty() {
+            let packs: Array = self.packs.iter().map(RecordedPack::to_inline).collect();
+            let mut templates = Table::new();
+            templates.insert("packs", value(packs));
+            doc.insert("templates", Item::Table(templates));
+        }
+
+        let mut files = Table::new();
+        for (path, hash) in &self.files {
+            files.insert(path, value(hash));
+        }
+        doc.insert("files", Item::Table(files));
+
+        writeln!(
+            f,
+            "# Written by boots; records how this project was generated"
+        )?;
+        write!(f, "{}", doc)
+    }
+}
+
+/// The tables of `.boots.toml`, as read back
+#[derive(Deserialize)]
+struct Document {
+    boots: BootsTable,
+    project: ProjectConfig,
+    workspace: Option<WorkspaceTable>,
+    #[serde(default)]
+    templates: TemplatesTable,
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct BootsTable {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct WorkspaceTable {
+    modules: Vec<Module>,
+}
+
+#[derive(Default, Deserialize)]
+struct TemplatesTable {
+    #[serde(default)]
+    packs: Vec<RecordedPack>,
+}
+
+impl FromStr for ProjectManifest {
+    type Err = BootsError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let doc: Document = toml_edit::de::from_str(text).map_err(|e| {
+            BootsError::Workspace(format!("invalid {}: {}", PROJECT_MANIFEST, e.message()))
+        })?;
+        Ok(Self {
+            boots_version: doc.boots.version,
+            modules: match doc.workspace {
+                Some(workspace) => workspace.modules,
+                None => doc.project.modules(),
+            },
+            config: doc.project,
+            packs: doc.templates.packs,
+            files: doc.files,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProjectType, parse_options};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip() {
+        let config =
+            parse_options(ProjectType::Service, "demo", Some("postgres,grpc,spa")).unwrap();
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("pack.toml"),
+            "[pack]\nname = \"company\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        let pack = TemplatePack::load(dir.path(), None).unwrap();
+        let mut manifest = ProjectManifest::new(&config, &[&pack]);
+        manifest.record(Path::new("crates/core/src/lib.rs"), "pub fn f() {}");
+        manifest.record(Path::new("Cargo.toml"), "[workspace]");
+
+        let text = manifest.to_string();
+        assert!(text.contains("type = \"service\""));
+        assert!(text.contains("persistence = \"postgres\""));
+        assert!(text.contains("\"crates/core/src/lib.rs\" = \"sha256:"));
+        let parsed = text.parse::<ProjectManifest>().unwrap();
+        assert_eq!(parsed, manifest);
+        assert_eq!(parsed.packs[0].path, fs::canonicalize(dir.path()).unwrap());
+        assert_eq!(parsed.templates().unwrap().packs().len(), 1);
+
+        // Written over, the pack no longer reproduces the generation
+        fs::write(
+            dir.path().join("pack.toml"),
+            "[pack]\nname = \"company\"\nversion = \"2.0.0\"\n",
+        )
+        .unwrap();
+        assert!(
+            parsed
+                .templates()
+                .unwrap_err()
+                .to_string()
+                .contains("generated with company 1.2.0")
+        );
+    }
+
+    #[test]
+    fn test_missing_keys_take_config_defaults() {
+        let text =
+            "[boots]\nversion = \"0.1.0\"\n\n[project]\nname = \"demo\"\ntype = \"service\"\n";
+        let manifest: ProjectManifest = text.parse().unwrap();
+        assert!(manifest.config.has_http);
+        assert_eq!(manifest.modules, manifest.config.modules());
+
+        let error = "[boots]\nversion = \"0.1.0\"\n[project]\nname = \"demo\"\ntype = \"web\"\n"
+            .parse::<ProjectManifest>()
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Workspace error: invalid .boots.toml")
+        );
+    }
+
+    #[test]
+    fn test_is_unmodified() {
+        let config = parse_options(ProjectType::Lib, "demo", None).unwrap();
+        let mut manifest = ProjectManifest::new(&config, &[]);
+        manifest.record(Path::new("README.md"), "# demo\n");
+
+        assert!(manifest.is_unmodified(Path::new("README.md"), "# demo\n"));
+        assert!(!manifest.is_unmodified(Path::new("README.md"), "# changed\n"));
+        assert!(!manifest.is_unmodified(Path::new("Makefile"), "# demo\n"));
+    }
+
+    #[test]
+    fn test_forget() {
+        let config = parse_options(ProjectType::Cli, "demo", Some("client")).unwrap();
+        let mut manifest = ProjectManifest::new(&config, &[]);
+        for path in [
+            "README.md",
+            "crates/client/Cargo.toml",
+            "crates/client/src/lib.rs",
+            "crates/client-ext/Cargo.toml",
+        ] {
+            manifest.record(Path::new(path), "");
+        }
+
+        manifest.forget(Path::new("README.md"));
+        manifest.forget(&Path::new("crates").join("client"));
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            ["crates/client-ext/Cargo.toml"]
+        );
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(""),
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use super::merge::merge3;
+use super::plan::GenerationPlan;
+use super::project_manifest::{PROJECT_MANIFEST, RecordedPack};
+use super::workspace::Workspace;
+use crate::config::{ProjectConfig, ProjectType};
+use crate::error::{BootsError, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What `Upgrade::apply` does to a file whose generated output changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeStatus {
+    /// Left untouched by the user, replaced with the new output
+    Updated,
+    /// Newly generated file
+    Added,
+    /// User changes and template changes merged without conflicts
+    Merged,
+    /// Merged with this many conflict blocks for the user to resolve
+    Conflicted(usize),
+    /// Left alone, for the given reason
+    Skipped(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct UpgradedFile {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    pub status: UpgradeStatus,
+    content: Option<String>,
+}
+
+/// The changes between the output of the boots version that generated a project and
+/// the current one, merged into the project's files
+#[derive(Debug, Clone)]
+pub struct Upgrade {
+    pub root: PathBuf,
+    pub from_version: String,
+    pub to_version: String,
+    pub files: Vec<UpgradedFile>,
+    manifest: Option<String>,
+}
+
+impl Upgrade {
+    /// Three-way merge the difference between `base` (the original output) and `plan`
+    /// (the same project generated now) into the files of `workspace`
+    pub fn new(
+        workspace: &Workspace,
+        base: &GenerationPlan,
+        plan: &GenerationPlan,
+    ) -> Result<Self> {
+        let manifest = workspace.manifest.as_ref().ok_or_else(|| {
+            BootsError::Workspace(format!(
+                "{} not found; upgrade needs the settings the project was generated with",
+                PROJECT_MANIFEST
+            ))
+        })?;
+        let to_version = env!("CARGO_PKG_VERSION").to_string();
+        let theirs_label = format!("boots {}", to_version);
+
+        let paths: BTreeSet<&Path> = base
+            .files
+            .iter()
+            .chain(&plan.files)
+            .map(|f| f.path.as_path())
+            .filter(|path| *path != Path::new(PROJECT_MANIFEST))
+            .collect();
+
+        let mut files = Vec::new();
+        for path in paths {
+            let old = base.file(path).map(|f| f.content.as_str());
+            let new = plan.file(path).map(|f| f.content.as_str());
+            if old == new {
+                continue;
+            }
+            let current = read_if_exists(&workspace.root.join(path))?;
+
+            let (status, content) = match (current.as_deref(), new) {
+                (None, None) => continue,
+                (Some(current), Some(new)) if current == new => continue,
+                (None, Some(_)) if old.is_some() => (UpgradeStatus::Skipped("deleted"), None),
+                (None, Some(new)) => (UpgradeStatus::Added, Some(new.to_string())),
+                (Some(_), None) => (UpgradeStatus::Skipped("no longer generated"), None),
+                (Some(current), Some(new)) if Some(current) == old => {
+                    (UpgradeStatus::Updated, Some(new.to_string()))
+                }
+                (Some(current), Some(new)) => {
+                    let merged = merge3(old.unwrap_or(""), current, new, "yours", &theirs_label);
+                    let status = match merged.conflicts {
+                        0 => UpgradeStatus::Merged,
+                        n => UpgradeStatus::Conflicted(n),
+                    };
+                    (status, Some(merged.text))
+                }
+            };
+            files.push(UpgradedFile {
+                path: path.to_path_buf(),
+                status,
+                content,
+            });
+        }
+
+        Ok(Self {
+            root: workspace.root.clone(),
+            from_version: manifest.boots_version.clone(),
+            to_version,
+            files,
+            manifest: plan.file(PROJECT_MANIFEST).map(|f| f.content.clone()),
+        })
+    }
+
+    /// Write the updated and merged files and record the new version in `.boots.toml`
+    pub fn apply(&self) -> Result<()> {
+        for file in &self.files {
+            if let Some(content) = &file.content {
+                let path = self.root.join(&file.path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, content)?;
+            }
+        }
+        if let Some(manifest) = &self.manifest {
+            fs::write(self.root.join(PROJECT_MANIFEST), manifest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate `config` with the `boots` executable at `boots` (e.g. an older release)
+/// in a scratch directory and read the output back. Only the recorded settings count:
+/// the user config, `BOOTS_*` variables and default hooks are kept out of the run.
+pub fn generate_with(
+    boots: &Path,
+    config: &ProjectConfig,
+    packs: &[RecordedPack],
+) -> Result<GenerationPlan> {
+    let scratch = std::env::temp_dir().join(format!("boots-upgrade-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&scratch);
+    let config_home = scratch.join(".config");
+    fs::create_dir_all(&config_home)?;
+
+    // Older releases lack some flags; pass those their help mentions
+    let help = Command::new(boots)
+        .args([config.project_type.as_str(), "--help"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+    let supports = |flag: &str| help.contains(flag);
+
+    let mut command = Command::new(boots);
+    command
+        .args([config.project_type.as_str(), &config.name])
+        .current_dir(&scratch);
+    for (key, _) in std::env::vars_os() {
+        if key.to_string_lossy().starts_with("BOOTS_") {
+            command.env_remove(key);
+        }
+    }
+    command
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("BOOTS_LICENSE", &config.license)
+        // Authors come from git config; pin them to the ones the project was made with
+        .env("GIT_CONFIG_COUNT", "2")
+        .env("GIT_CONFIG_KEY_0", "user.name")
+        .env("GIT_CONFIG_VALUE_0", &config.author_name)
+        .env("GIT_CONFIG_KEY_1", "user.email")
+        .env("GIT_CONFIG_VALUE_1", &config.author_email);
+    let options = config.options();
+    if config.project_type != ProjectType::Lib && !options.is_empty() {
+        command.args(["--options", &options.join(",")]);
+    }
+    if supports("--no-hooks") {
+        command.arg("--no-hooks");
+    }
+    if !config.repository.is_empty() && supports("--repository") {
+        command.args(["--repository", &config.repository]);
+    }
+    // The command line takes a single pack, the one with the highest priority
+    if let Some(pack) = packs.first() {
+        command.arg("--template-pack").arg(&pack.path);
+        if let Some(git_ref) = &pack.git_ref {
+            command.args(["--pack-ref", git_ref]);
+        }
+    }
+
+    let result = command
+        .output()
+        .map_err(|e| BootsError::Workspace(format!("could not run {}: {}", boots.display(), e)))
+        .and_then(|output| {
+            if !output.status.success() {
+                return Err(BootsError::Workspace(format!(
+                    "{} failed: {}",
+                    boots.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            GenerationPlan::from_dir(scratch.join(&config.name))
+        });
+    let _ = fs::remove_dir_all(&scratch);
+    result
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_options;
+    use crate::generator::{PlannedFile, ProjectGenerator};
+    use tempfile::TempDir;
+
+    fn planned(path: &str, content: &str) -> PlannedFile {
+        PlannedFile {
+            path: PathBuf::from(path),
+            template: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_upgrade_statuses() {
+        let temp = TempDir::new().unwrap();
+        let config = parse_options(ProjectType::Lib, "demo", None).unwrap();
+        ProjectGenerator::new(config).generate(temp.path()).unwrap();
+        let workspace = Workspace::open(&temp.path().join("demo")).unwrap();
+        let root = &workspace.root;
+
+        let mut base = GenerationPlan::new(root);
+        let mut plan = GenerationPlan::new(root);
+        for (path, old, new) in [
+            ("untouched.txt", "a\n", "b\n"),
+            ("edited.txt", "a\nb\nc\n", "a\nb\nC\n"),
+            ("conflict.txt", "a\n", "theirs\n"),
+            ("deleted.txt", "a\n", "b\n"),
+            ("same.txt", "a\n", "a\n"),
+        ] {
+            base.add(planned(path, old));
+            plan.add(planned(path, new));
+        }
+        base.add(planned("obsolete.txt", "a\n"));
+        plan.add(planned("added.txt", "new\n"));
+
+        fs::write(root.join("untouched.txt"), "a\n").unwrap();
+        fs::write(root.join("edited.txt"), "A\nb\nc\n").unwrap();
+        fs::write(root.join("conflict.txt"), "mine\n").unwrap();
+        fs::write(root.join("same.txt"), "changed\n").unwrap();
+        fs::write(root.join("obsolete.txt"), "a\n").unwrap();
+
+        let upgrade = Upgrade::new(&workspace, &base, &plan).unwrap();
+        let statuses: Vec<(String, UpgradeStatus)> = upgrade
+            .files
+            .iter()
+            .map(|f| (f.path.display().to_string(), f.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("added.txt".to_string(), UpgradeStatus::Added),
+                ("conflict.txt".to_string(), UpgradeStatus::Conflicted(1)),
+                ("deleted.txt".to_string(), UpgradeStatus::Skipped("deleted")),
+                ("edited.txt".to_string(), UpgradeStatus::Merged),
+                (
+                    "obsolete.txt".to_string(),
+                    UpgradeStatus::Skipped("no longer generated")
+                ),
+                ("untouched.txt".to_string(), UpgradeStatus::Updated),
+            ]
+        );
+
+        upgrade.apply().unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("edited.txt")).unwrap(),
+            "A\nb\nC\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("untouched.txt")).unwrap(),
+            "b\n"
+        );
+        assert!(!root.join("deleted.txt").exists());
+        assert!(
+            fs::read_to_string(root.join("conflict.txt"))
+                .unwrap()
+                .starts_with("<<<<<<< yours\nmine\n")
+        );
+    }
+
+    #[test]
+    fn test_upgrade_requires_project_manifest() {
+        let temp = TempDir::new().unwrap();
+        let config = parse_options(ProjectType::Lib, "demo", None).unwrap();
+        ProjectGenerator::new(config).generate(temp.path()).unwrap();
+        let root = temp.path().join("demo");
+        fs::remove_file(root.join(PROJECT_MANIFEST)).unwrap();
+
+        let workspace = Workspace::open(&root).unwrap();
+        let plan = GenerationPlan::new(&root);
+        assert!(matches!(
+            Upgrade::new(&workspace, &plan, &plan),
+            Err(BootsError::Workspace(_))
+        ));
+    }
+}
@@ -0,0 +1,878 @@
+use super::diff::{DiffStatus, FileDiff, unified_diff};
+use super::plan::{ConflictPolicy, GenerationPlan};
+use super::project_manifest::{PROJECT_MANIFEST, ProjectManifest};
+use crate::config::options::parse_option_list;
+use crate::config::parser::build_config;
+use crate::config::user::split_author;
+use crate::config::{
+    FrontendType, Module, PersistenceType, ProjectConfig, ProjectType, UserConfig,
+};
+use crate::error::{BootsError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, InlineTable, value};
+
+/// A project previously generated by boots
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Project name, the prefix of every crate name (`<name>-core`)
+    pub name: String,
+    pub project_type: ProjectType,
+    /// Modules listed in `[workspace] members`
+    pub modules: Vec<Module>,
+    /// `.boots.toml`, if the project has one
+    pub manifest: Option<ProjectManifest>,
+}
+
+impl Workspace {
+    /// Find the workspace containing `dir`, walking up to the nearest `Cargo.toml`
+    /// with a `[workspace]` table
+    pub fn discover(dir: &Path) -> Result<Self> {
+        let root = dir
+            .ancestors()
+            .find(|d| {
+                read_manifest(&d.join("Cargo.toml")).is_ok_and(|doc| doc.contains_key("workspace"))
+            })
+            .ok_or_else(|| {
+                BootsError::Workspace(format!(
+                    "no Cargo workspace found in {} or its parents",
+                    dir.display()
+                ))
+            })?;
+        Self::open(root)
+    }
+
+    pub fn open(root: &Path) -> Result<Self> {
+        let doc = read_manifest(&root.join("Cargo.toml"))?;
+        let modules: Vec<Module> = members(&doc)?
+            .iter()
+            .filter_map(|m| m.as_str()?.strip_prefix("crates/")?.parse().ok())
+            .collect();
+
+        let core = read_manifest(&root.join("crates/core/Cargo.toml"))?;
+        let name = core
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .and_then(|n| n.strip_suffix("-core"))
+            .ok_or_else(|| {
+                BootsError::Workspace(
+                    "crates/core/Cargo.toml is not a `<project>-core` package".to_string(),
+                )
+            })?
+            .to_string();
+
+        let manifest = ProjectManifest::load(root)?;
+        let project_type = if let Some(manifest) = &manifest {
+            manifest.config.project_type
+        } else if root.join("crates/core/src/board").is_dir() {
+            ProjectType::Sample
+        } else if modules.contains(&Module::Runtime) {
+            ProjectType::Service
+        } else if modules.contains(&Module::Cli) {
+            ProjectType::Cli
+        } else {
+            ProjectType::Lib
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            name,
+            project_type,
+            modules,
+            manifest,
+        })
+    }
+
+    /// Settings the project was generated with: those in `.boots.toml`, or else inferred
+    /// from its crates and files
+    pub fn config(&self) -> Result<ProjectConfig> {
+        if let Some(manifest) = &self.manifest {
+            return Ok(manifest.config.clone());
+        }
+
+        // The name is what the project is called already, valid or not by today's rules
+        let defaults = UserConfig::default();
+        if self.project_type == ProjectType::Sample {
+            return build_config(self.project_type, &self.name, Some("sample"), &defaults);
+        }
+        let mut config = build_config(self.project_type, &self.name, None, &defaults)?;
+        config.has_grpc = self.root.join("proto").is_dir();
+        config.has_client = self.modules.contains(&Module::Client);
+        if self.modules.contains(&Module::Persistence) {
+            let cargo = fs::read_to_string(self.root.join("crates/persistence/Cargo.toml"))?;
+            config.persistence = Some(if cargo.contains("\"postgres\"") {
+                PersistenceType::Postgres
+            } else if cargo.contains("\"sqlite\"") {
+                PersistenceType::Sqlite
+            } else {
+                PersistenceType::File
+            });
+        }
+        if self.root.join("frontend").is_dir() {
+            config.frontend = Some(if self.root.join("frontend/app").is_dir() {
+                FrontendType::Ssr
+            } else {
+                FrontendType::Spa
+            });
+        }
+
+        // Authors as written to the workspace Cargo.toml: "Name <email>"
+        let doc = read_manifest(&self.root.join("Cargo.toml"))?;
+        let package = doc.get("workspace").and_then(|w| w.get("package"));
+        let author = package
+            .and_then(|p| p.get("authors"))
+            .and_then(|a| a.as_array())
+            .and_then(|a| a.get(0))
+            .and_then(|a| a.as_str());
+        if let Some(author) = author {
+            (config.author_name, config.author_email) = split_author(author);
+        }
+        let repository = package
+            .and_then(|p| p.get("repository"))
+            .and_then(|r| r.as_str());
+        config.repository = repository.unwrap_or_default().to_string();
+        if let Some(license) = package
+            .and_then(|p| p.get("license"))
+            .and_then(|l| l.as_str())
+        {
+            config.license = license.to_string();
+        }
+        Ok(config)
+    }
+
+    /// The project as `project_type` with `options` instead of its own, keeping the author,
+    /// repository, license and module settings it already has
+    pub fn config_with(
+        &self,
+        project_type: ProjectType,
+        options: Option<&str>,
+        defaults: &UserConfig,
+    ) -> Result<ProjectConfig> {
+        let recorded = self.config()?;
+        Ok(ProjectConfig {
+            author_name: recorded.author_name,
+            author_email: recorded.author_email,
+            repository: recorded.repository,
+            license: recorded.license,
+            module_settings: recorded.module_settings,
+            ..build_config(project_type, &self.name, options, defaults)?
+        })
+    }
+
+    /// How the files of this project differ from `plan`, a fresh generation of it.
+    /// Files the project added itself are not compared.
+    pub fn diff(&self, plan: &GenerationPlan) -> Result<Vec<FileDiff>> {
+        let mut diffs = Vec::new();
+        for file in &plan.files {
+            if file.path == Path::new(PROJECT_MANIFEST) {
+                continue;
+            }
+            let path = file.path.display();
+            let (status, diff) = match fs::read_to_string(self.root.join(&file.path)) {
+                Ok(current) => (
+                    DiffStatus::Modified,
+                    unified_diff(
+                        &file.content,
+                        &current,
+                        &format!("a/{}", path),
+                        &format!("b/{}", path),
+                    ),
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    // Without `.boots.toml` there is no telling the two apart
+                    let generated = self
+                        .manifest
+                        .as_ref()
+                        .is_some_and(|m| m.is_recorded(&file.path));
+                    let status = if generated {
+                        DiffStatus::Deleted
+                    } else {
+                        DiffStatus::Added
+                    };
+                    let diff = unified_diff(&file.content, "", &format!("a/{}", path), "/dev/null");
+                    (status, diff)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if status != DiffStatus::Modified || !diff.is_empty() {
+                diffs.push(FileDiff {
+                    path: file.path.clone(),
+                    status,
+                    diff,
+                });
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Config for generating `module` into this project, tuned by `options` (e.g. `sqlite`);
+    /// a bare `persistence` uses the preferred backend of `defaults`
+    pub fn config_for(
+        &self,
+        module: Module,
+        options: Option<&str>,
+        defaults: &UserConfig,
+    ) -> Result<ProjectConfig> {
+        if self.modules.contains(&module) {
+            return Err(BootsError::Workspace(format!(
+                "module '{}' already exists",
+                module
+            )));
+        }
+
+        let flag = match module {
+            Module::Client => "client",
+            Module::Persistence => "persistence",
+            _ => {
+                return Err(BootsError::InvalidOption(format!(
+                    "module '{}' cannot be added, only client and persistence are optional",
+                    module
+                )));
+            }
+        };
+        let options = match options {
+            Some(options) => format!("{},{}", options, flag),
+            None => flag.to_string(),
+        };
+        // Only the module's own settings can change; the rest of the project stays as it is
+        if let Some(unused) = parse_option_list(self.project_type, &options)?
+            .iter()
+            .find(|option| option.spec.kind.group() != flag)
+        {
+            return Err(BootsError::InvalidOption(format!(
+                "'{}' does not apply when adding the {} module",
+                unused.token(),
+                module
+            )));
+        }
+
+        let parsed = build_config(self.project_type, &self.name, Some(&options), defaults)?;
+        let mut config = self.config()?;
+        match module {
+            Module::Client => config.has_client = true,
+            _ => config.persistence = parsed.persistence,
+        }
+
+        if !config.modules().contains(&module) {
+            return Err(BootsError::InvalidOption(format!(
+                "module '{}' is not available for {} projects",
+                module, self.project_type
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Write `plan` (from `ProjectGenerator::plan_module` with `config` from `config_for`),
+    /// then add the crate to the workspace members and as a path dependency of the crate
+    /// that uses it. Files of the plan outside the crate that already exist are kept.
+    /// Returns the manifests that were edited, relative to the root.
+    pub fn add_module(
+        &self,
+        module: Module,
+        config: &ProjectConfig,
+        plan: &GenerationPlan,
+    ) -> Result<Vec<PathBuf>> {
+        let crate_dir = self.root.join("crates").join(module.as_str());
+        if crate_dir.exists() {
+            return Err(BootsError::DirectoryExists(crate_dir.display().to_string()));
+        }
+        let existing = plan.conflicts();
+
+        let mut edits = Vec::new();
+
+        let mut doc = read_manifest(&self.root.join("Cargo.toml"))?;
+        add_member(&mut doc, &format!("crates/{}", module))?;
+        edits.push((PathBuf::from("Cargo.toml"), doc.to_string()));
+
+        if let Some(consumer) = consumer(module, &self.modules) {
+            let path = Path::new("crates")
+                .join(consumer.as_str())
+                .join("Cargo.toml");
+            let mut doc = read_manifest(&self.root.join(&path))?;
+            add_path_dependency(
+                &mut doc,
+                &format!("{}-{}", self.name, module),
+                &format!("../{}", module),
+            );
+            edits.push((path, doc.to_string()));
+        }
+
+        if let Some(manifest) = &self.manifest {
+            let mut manifest = manifest.clone();
+            manifest.config = config.clone();
+            manifest.modules.push(module);
+            for file in plan.files.iter().filter(|f| !existing.contains(&f.path)) {
+                manifest.record(&file.path, &file.content);
+            }
+            self.record_edits(manifest, &mut edits);
+        }
+
+        // Every manifest parsed fine, so only now touch the disk. If a manifest cannot be
+        // written, the new files go again
+        plan.apply_into(ConflictPolicy::Skip)?;
+        if let Err(e) = self.write_edits(&edits) {
+            for file in plan.files.iter().filter(|f| !existing.contains(&f.path)) {
+                let _ = fs::remove_file(self.root.join(&file.path));
+            }
+            let _ = fs::remove_dir_all(&crate_dir);
+            return Err(e);
+        }
+        Ok(edits.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// Delete `crates/<module>`, drop it from the workspace members and from the
+    /// dependencies of every other crate. Refuses modules the project type requires.
+    pub fn remove_module(&self, module: Module) -> Result<RemovedModule> {
+        if !self.modules.contains(&module) {
+            return Err(BootsError::Workspace(format!(
+                "module '{}' is not part of this project",
+                module
+            )));
+        }
+        if self.project_type.required_modules().contains(&module) {
+            return Err(BootsError::Workspace(format!(
+                "module '{}' is required by {} projects",
+                module, self.project_type
+            )));
+        }
+
+        let mut edits = Vec::new();
+
+        let mut doc = read_manifest(&self.root.join("Cargo.toml"))?;
+        remove_member(&mut doc, &format!("crates/{}", module))?;
+        edits.push((PathBuf::from("Cargo.toml"), doc.to_string()));
+
+        let crate_name = format!("{}-{}", self.name, module);
+        for other in self.modules.iter().filter(|m| **m != module) {
+            let path = Path::new("crates").join(other.as_str()).join("Cargo.toml");
+            let mut doc = read_manifest(&self.root.join(&path))?;
+            if remove_dependency(&mut doc, &crate_name) {
+                edits.push((path, doc.to_string()));
+            }
+        }
+
+        if let Some(manifest) = &self.manifest {
+            let mut manifest = manifest.clone();
+            match module {
+                Module::Client => manifest.config.has_client = false,
+                _ => manifest.config.persistence = None,
+            }
+            manifest.config.module_settings.remove(&module);
+            manifest.modules.retain(|m| *m != module);
+            manifest.forget(&Path::new("crates").join(module.as_str()));
+            self.record_edits(manifest, &mut edits);
+        }
+
+        // The manifests first: should one fail, the crate is still there and still wired
+        self.write_edits(&edits)?;
+        fs::remove_dir_all(self.root.join("crates").join(module.as_str()))?;
+
+        let mut references = Vec::new();
+        find_references(
+            &self.root,
+            &self.root.join("crates"),
+            &crate_name.replace('-', "_"),
+            &mut references,
+        )?;
+
+        Ok(RemovedModule {
+            edited: edits.into_iter().map(|(path, _)| path).collect(),
+            references,
+        })
+    }
+
+    /// Write `edits`, restoring the files already written if one fails
+    fn write_edits(&self, edits: &[(PathBuf, String)]) -> Result<()> {
+        let mut written = Vec::new();
+        for (path, content) in edits {
+            let path = self.root.join(path);
+            let previous = fs::read_to_string(&path).ok();
+            if let Err(e) = fs::write(&path, content) {
+                for (path, previous) in written.into_iter().rev() {
+                    let _ = match previous {
+                        Some(previous) => fs::write(&path, previous),
+                        None => fs::remove_file(&path),
+                    };
+                }
+                return Err(e.into());
+            }
+            written.push((path, previous));
+        }
+        Ok(())
+    }
+
+    /// Update the hashes of edited files in `manifest` and queue it as an edit too
+    fn record_edits(&self, mut manifest: ProjectManifest, edits: &mut Vec<(PathBuf, String)>) {
+        for (path, content) in edits.iter() {
+            manifest.record(path, content);
+        }
+        edits.push((PathBuf::from(PROJECT_MANIFEST), manifest.to_string()));
+    }
+}
+
+/// What `Workspace::remove_module` changed and what is left for the user to fix
+#[derive(Debug, Clone, Default)]
+pub struct RemovedModule {
+    /// Manifests that were edited, relative to the root
+    pub edited: Vec<PathBuf>,
+    /// Rust source lines still referring to the removed crate
+    pub references: Vec<SourceReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceReference {
+    /// Relative to the workspace root
+    pub path: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+/// The crate that depends on an optional module
+fn consumer(module: Module, modules: &[Module]) -> Option<Module> {
+    let candidates: &[Module] = match module {
+        Module::Client => &[Module::Cli],
+        Module::Persistence => &[Module::Api, Module::Cli],
+        _ => &[],
+    };
+    candidates.iter().copied().find(|m| modules.contains(m))
+}
+
+fn read_manifest(path: &Path) -> Result<DocumentMut> {
+    let text = fs::read_to_string(path)?;
+    text.parse()
+        .map_err(|e| BootsError::Workspace(format!("{}: {}", path.display(), e)))
+}
+
+fn members(doc: &DocumentMut) -> Result<&toml_edit::Array> {
+    doc.get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .ok_or_else(|| BootsError::Workspace("Cargo.toml has no [workspace] members".to_string()))
+}
+
+/// Append to `[workspace] members`, formatted like the existing entries
+fn add_member(doc: &mut DocumentMut, member: &str) -> Result<()> {
+    let members = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("members"))
+        .and_then(|m| m.as_array_mut())
+        .ok_or_else(|| {
+            BootsError::Workspace("Cargo.toml has no [workspace] members".to_string())
+        })?;
+    if members.iter().any(|m| m.as_str() == Some(member)) {
+        return Ok(());
+    }
+
+    let decor = members.iter().last().map(|last| last.decor().clone());
+    members.push(member);
+    if let Some(decor) = decor
+        && let Some(added) = members.get_mut(members.len() - 1)
+    {
+        *added.decor_mut() = decor;
+    }
+    Ok(())
+}
+
+fn add_path_dependency(doc: &mut DocumentMut, name: &str, path: &str) {
+    let Some(deps) = doc
+        .entry("dependencies")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+    else {
+        return;
+    };
+    if deps.contains_key(name) {
+        return;
+    }
+    let mut dependency = InlineTable::new();
+    dependency.insert("path", path.into());
+    deps.insert(name, value(dependency));
+}
+
+fn remove_member(doc: &mut DocumentMut, member: &str) -> Result<()> {
+    let members = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("members"))
+        .and_then(|m| m.as_array_mut())
+        .ok_or_else(|| {
+            BootsError::Workspace("Cargo.toml has no [workspace] members".to_string())
+        })?;
+    members.retain(|m| m.as_str() != Some(member));
+    Ok(())
+}
+
+/// Remove `name` from every dependency table; `true` if it was present
+fn remove_dependency(doc: &mut DocumentMut, name: &str) -> bool {
+    let mut removed = false;
+    for table in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        if let Some(deps) = doc.get_mut(table).and_then(|t| t.as_table_like_mut()) {
+            removed |= deps.remove(name).is_some();
+        }
+    }
+    removed
+}
+
+/// Lines of `.rs` files under `dir` that mention the `ident` crate
+fn find_references(
+    root: &Path,
+    dir: &Path,
+    ident: &str,
+    references: &mut Vec<SourceReference>,
+) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                find_references(root, &path, ident, references)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let text = fs::read_to_string(&path)?;
+            for (index, line) in text.lines().enumerate() {
+                if contains_ident(line, ident) {
+                    references.push(SourceReference {
+                        path: path.strip_prefix(root).unwrap_or(&path).to_path_buf(),
+                        line: index + 1,
+                        text: line.trim().to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `ident` as a whole word, so `demo_client` does not match `demo_client_ext`
+fn contains_ident(line: &str, ident: &str) -> bool {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    line.match_indices(ident).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + ident.len()..].chars().next();
+        !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PersistenceType, parse_options};
+    use crate::generator::{PlannedFile, ProjectGenerator};
+    use tempfile::TempDir;
+
+    fn generate(temp: &TempDir, project_type: ProjectType, options: Option<&str>) -> Workspace {
+        let config = parse_options(project_type, "demo", options).unwrap();
+        ProjectGenerator::new(config).generate(temp.path()).unwrap();
+        Workspace::discover(&temp.path().join("demo/crates/core/src")).unwrap()
+    }
+
+    #[test]
+    fn test_discover_detects_project() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, Some("client"));
+        assert_eq!(workspace.root, temp.path().join("demo"));
+        assert_eq!(workspace.name, "demo");
+        assert_eq!(workspace.project_type, ProjectType::Cli);
+        assert_eq!(
+            workspace.modules,
+            vec![Module::Core, Module::Cli, Module::Client]
+        );
+    }
+
+    #[test]
+    fn test_config_inferred_without_project_manifest() {
+        let temp = TempDir::new().unwrap();
+        let generated = generate(&temp, ProjectType::Service, Some("sqlite,grpc,ssr"));
+        let recorded = generated.config().unwrap();
+
+        fs::remove_file(generated.root.join(PROJECT_MANIFEST)).unwrap();
+        let inferred = Workspace::open(&generated.root).unwrap().config().unwrap();
+        assert_eq!(inferred, recorded);
+    }
+
+    #[test]
+    fn test_diff_against_fresh_generation() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Lib, None);
+        let plan = ProjectGenerator::new(workspace.config().unwrap())
+            .plan_at(&workspace.root)
+            .unwrap();
+        assert!(workspace.diff(&plan).unwrap().is_empty());
+
+        let readme = workspace.root.join("README.md");
+        let content = fs::read_to_string(&readme).unwrap();
+        fs::write(&readme, content.replacen('\n', "\nDrifted\n", 1)).unwrap();
+        fs::remove_file(workspace.root.join("Makefile")).unwrap();
+
+        let mut plan = plan;
+        plan.add(PlannedFile {
+            path: PathBuf::from("deny.toml"),
+            template: None,
+            content: "[bans]\n".to_string(),
+        });
+
+        let diffs = workspace.diff(&plan).unwrap();
+        let statuses: Vec<(&Path, DiffStatus)> =
+            diffs.iter().map(|d| (d.path.as_path(), d.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                (Path::new("Makefile"), DiffStatus::Deleted),
+                (Path::new("README.md"), DiffStatus::Modified),
+                (Path::new("deny.toml"), DiffStatus::Added),
+            ]
+        );
+        assert!(diffs[1].diff.contains("\n+Drifted\n"));
+        assert!(
+            diffs[2]
+                .diff
+                .starts_with("--- a/deny.toml\n+++ /dev/null\n")
+        );
+    }
+
+    #[test]
+    fn test_add_module_keeps_formatting() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, None);
+        let root_manifest = fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap();
+
+        let config = workspace
+            .config_for(Module::Persistence, Some("sqlite"), &UserConfig::default())
+            .unwrap();
+        let plan = ProjectGenerator::new(config.clone())
+            .plan_module(
+                &workspace.root,
+                &workspace.config().unwrap(),
+                Module::Persistence,
+            )
+            .unwrap();
+        let edited = workspace
+            .add_module(Module::Persistence, &config, &plan)
+            .unwrap();
+        assert_eq!(
+            edited,
+            vec![
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("crates/cli/Cargo.toml"),
+                PathBuf::from(PROJECT_MANIFEST),
+            ]
+        );
+
+        let manifest = ProjectManifest::load(&workspace.root).unwrap().unwrap();
+        assert!(manifest.modules.contains(&Module::Persistence));
+        assert_eq!(manifest.config.persistence, Some(PersistenceType::Sqlite));
+        assert!(manifest.files.contains_key("crates/persistence/Cargo.toml"));
+
+        let updated = fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap();
+        assert_eq!(
+            updated,
+            root_manifest.replace(
+                "    \"crates/cli\",\n",
+                "    \"crates/cli\",\n    \"crates/persistence\",\n"
+            )
+        );
+
+        let cli = fs::read_to_string(workspace.root.join("crates/cli/Cargo.toml")).unwrap();
+        assert!(cli.contains("demo-persistence = { path = \"../persistence\" }"));
+        let persistence =
+            fs::read_to_string(workspace.root.join("crates/persistence/Cargo.toml")).unwrap();
+        assert!(persistence.contains("\"sqlite\"]"));
+        assert!(
+            workspace
+                .root
+                .join("crates/persistence/src/lib.rs")
+                .exists()
+        );
+
+        // Just as if the project had been generated with sqlite
+        assert!(workspace.root.join(".env.example").exists());
+        let workspace = Workspace::open(&workspace.root).unwrap();
+        let fresh = ProjectGenerator::new(config)
+            .plan_at(&workspace.root)
+            .unwrap();
+        let drift: Vec<PathBuf> = workspace
+            .diff(&fresh)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.path)
+            .collect();
+        assert!(drift.is_empty(), "{:?}", drift);
+    }
+
+    #[test]
+    fn test_remove_module_unwires_crate() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, Some("client"));
+        let root_manifest = fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap();
+
+        let config = workspace
+            .config_for(Module::Persistence, None, &UserConfig::default())
+            .unwrap();
+        let plan = ProjectGenerator::new(config.clone())
+            .plan_module(
+                &workspace.root,
+                &workspace.config().unwrap(),
+                Module::Persistence,
+            )
+            .unwrap();
+        workspace
+            .add_module(Module::Persistence, &config, &plan)
+            .unwrap();
+        let main_rs = workspace.root.join("crates/cli/src/main.rs");
+        let main = fs::read_to_string(&main_rs).unwrap();
+        fs::write(&main_rs, format!("use demo_persistence::Store;\n{}", main)).unwrap();
+
+        let workspace = Workspace::open(&workspace.root).unwrap();
+        let removed = workspace.remove_module(Module::Persistence).unwrap();
+
+        assert!(!workspace.root.join("crates/persistence").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap(),
+            root_manifest
+        );
+        let cli = fs::read_to_string(workspace.root.join("crates/cli/Cargo.toml")).unwrap();
+        assert!(!cli.contains("demo-persistence"));
+        let manifest = ProjectManifest::load(&workspace.root).unwrap().unwrap();
+        assert_eq!(manifest.config.persistence, None);
+        assert!(!manifest.modules.contains(&Module::Persistence));
+        assert!(
+            !manifest
+                .files
+                .keys()
+                .any(|p| p.starts_with("crates/persistence/"))
+        );
+        assert_eq!(
+            removed.references,
+            vec![SourceReference {
+                path: PathBuf::from("crates/cli/src/main.rs"),
+                line: 1,
+                text: "use demo_persistence::Store;".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remove_module_keeps_crate_when_manifests_fail() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, Some("client"));
+        let root_manifest = fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap();
+
+        fs::remove_file(workspace.root.join(PROJECT_MANIFEST)).unwrap();
+        fs::create_dir(workspace.root.join(PROJECT_MANIFEST)).unwrap();
+        assert!(workspace.remove_module(Module::Client).is_err());
+        assert!(workspace.root.join("crates/client/src").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap(),
+            root_manifest
+        );
+    }
+
+    #[test]
+    fn test_name_is_not_checked_again() {
+        // "test" is reserved now, but a project may have been generated under it before
+        let temp = TempDir::new().unwrap();
+        let config = ProjectConfig {
+            name: "test".to_string(),
+            ..parse_options(ProjectType::Cli, "demo", Some("client")).unwrap()
+        };
+        ProjectGenerator::new(config).generate(temp.path()).unwrap();
+        let root = temp.path().join("test");
+        fs::remove_file(root.join(PROJECT_MANIFEST)).unwrap();
+        let workspace = Workspace::open(&root).unwrap();
+
+        assert_eq!(workspace.config().unwrap().name, "test");
+        let sqlite = workspace
+            .config_with(ProjectType::Cli, Some("sqlite"), &UserConfig::default())
+            .unwrap();
+        assert_eq!(sqlite.persistence, Some(PersistenceType::Sqlite));
+        assert!(
+            workspace
+                .config_for(Module::Persistence, None, &UserConfig::default())
+                .is_ok()
+        );
+        workspace.remove_module(Module::Client).unwrap();
+        assert!(!root.join("crates/client").exists());
+    }
+
+    #[test]
+    fn test_remove_refuses_required_module() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, Some("client"));
+        assert!(workspace.remove_module(Module::Cli).is_err());
+        assert!(workspace.remove_module(Module::Persistence).is_err());
+        assert!(workspace.root.join("crates/cli").exists());
+    }
+
+    #[test]
+    fn test_contains_ident() {
+        assert!(contains_ident("use demo_client::Client;", "demo_client"));
+        assert!(!contains_ident("use demo_client_ext::X;", "demo_client"));
+        assert!(!contains_ident("my_demo_client", "demo_client"));
+    }
+
+    #[test]
+    fn test_config_for_rejects_unavailable_modules() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Service, None);
+        assert!(
+            workspace
+                .config_for(Module::Client, None, &UserConfig::default())
+                .is_err()
+        );
+        assert!(
+            workspace
+                .config_for(Module::Api, None, &UserConfig::default())
+                .is_err()
+        );
+        assert!(
+            workspace
+                .config_for(Module::Persistence, None, &UserConfig::default())
+                .is_ok()
+        );
+
+        // Settings of other modules would be dropped, so they are refused
+        let error = workspace
+            .config_for(
+                Module::Persistence,
+                Some("sqlite,grpc"),
+                &UserConfig::default(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid option: 'grpc' does not apply when adding the persistence module"
+        );
+    }
+
+    #[test]
+    fn test_add_module_removes_crate_when_manifests_fail() {
+        let temp = TempDir::new().unwrap();
+        let workspace = generate(&temp, ProjectType::Cli, None);
+        let config = workspace
+            .config_for(Module::Persistence, None, &UserConfig::default())
+            .unwrap();
+        let plan = ProjectGenerator::new(config.clone())
+            .plan_module(
+                &workspace.root,
+                &workspace.config().unwrap(),
+                Module::Persistence,
+            )
+            .unwrap();
+        let root_manifest = fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap();
+
+        // A directory where the project manifest should be written
+        fs::remove_file(workspace.root.join(PROJECT_MANIFEST)).unwrap();
+        fs::create_dir(workspace.root.join(PROJECT_MANIFEST)).unwrap();
+        assert!(
+            workspace
+                .add_module(Module::Persistence, &config, &plan)
+                .is_err()
+        );
+        assert!(!workspace.root.join("crates/persistence").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.root.join("Cargo.toml")).unwrap(),
+            root_manifest
+        );
+    }
+}
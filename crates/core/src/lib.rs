@@ -3,7 +3,13 @@ pub mod error;
 pub mod generator;
 pub mod template;
 
-pub use config::{Module, PersistenceType, ProjectConfig, ProjectType, parse_options};
+pub use config::{
+    Module, PersistenceType, ProjectConfig, ProjectType, UserConfig, load_config, parse_options,
+    validate_name,
+};
 pub use error::{BootsError, Result};
-pub use generator::ProjectGenerator;
-pub use template::{TemplateEngine, Templates};
+pub use generator::{
+    GenerationPlan, PlannedFile, ProjectGenerator, ProjectManifest, Upgrade, UpgradeStatus,
+    Workspace,
+};
+pub use template::{TemplateEngine, TemplatePack, TemplateSource, Templates};
@@ -1,27 +1,299 @@
-use std::collections::HashMap;
+use super::filters::Filter;
+use super::source::TemplateSource;
+use crate::error::{BootsError, Result};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 
+/// A value that can be bound to a template variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Bool(bool),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Truthiness used by `{{#if}}`: empty strings, `false` and empty lists/maps are falsy.
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::String(s) => !s.is_empty(),
+            Value::Bool(b) => *b,
+            Value::List(items) => !items.is_empty(),
+            Value::Map(map) => !map.is_empty(),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Value::String(s) => s.clone(),
+            Value::Bool(b) => b.to_string(),
+            Value::List(items) => items
+                .iter()
+                .map(Value::to_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+            Value::Map(_) => String::new(),
+        }
+    }
+
+    fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        let mut current = self;
+        for key in path {
+            match current {
+                Value::Map(map) => current = map.get(*key)?,
+                _ => return None,
+            }
+        }
+        Some(current)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::String(value.clone())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Value::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<BTreeMap<String, T>> for Value {
+    fn from(map: BTreeMap<String, T>) -> Self {
+        Value::Map(map.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+/// Renders `{{name}}` placeholders plus `{{#if}}`, `{{#each}}` and `{{else}}` blocks.
+/// Conditions combine `!`, `==`, `!=`, `&&` and `||`, e.g. `{{#if has_grpc && !has_http}}`.
+///
+/// Templates are tokenized once and rendered in a single pass, so inserted values are
+/// never scanned for placeholders again. `\{{` renders a literal `{{`, which is how
+/// templates emit GitHub Actions expressions such as `$\{{ secrets.TOKEN }}`.
+///
+/// `{{> path/to/partial}}` includes another template from the engine's `TemplateSource`
+/// (the embedded set unless overridden), rendered with the same
+/// variables; include cycles are reported as errors.
+///
+/// Anything between `{{` and `}}` that is not a valid tag (e.g. JSX `style={{ ... }}`)
+/// is left untouched. Placeholders for variables that were never set are written out
+/// verbatim, or reported as an error in strict mode.
+#[derive(Debug, Clone)]
 pub struct TemplateEngine {
-    variables: HashMap<String, String>,
+    variables: HashMap<String, Value>,
+    strict: bool,
+    templates: TemplateSource,
 }
 
 impl TemplateEngine {
     pub fn new() -> Self {
         Self {
             variables: HashMap::new(),
+            strict: false,
+            templates: TemplateSource::embedded(),
         }
     }
 
-    pub fn set(&mut self, key: &str, value: &str) -> &mut Self {
-        self.variables.insert(key.to_string(), value.to_string());
+    /// Source used to resolve `{{> partial}}` includes
+    pub fn templates(&mut self, templates: TemplateSource) -> &mut Self {
+        self.templates = templates;
         self
     }
 
-    pub fn render(&self, template: &str) -> String {
-        let mut result = template.to_string();
-        for (key, value) in &self.variables {
-            result = result.replace(&format!("{{{{{}}}}}", key), value);
+    /// In strict mode, rendering fails if any `{{placeholder}}` has no value
+    pub fn strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) -> &mut Self {
+        self.variables.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Evaluate an `{{#if}}` condition such as `has_grpc && persistence == "postgres"`
+    pub fn eval(&self, condition: &str) -> Result<bool> {
+        let condition = parse_condition(condition.trim())
+            .ok_or_else(|| BootsError::Template(format!("invalid condition: {}", condition)))?;
+        Ok(self.evaluate(&condition, &[]))
+    }
+
+    pub fn render(&self, template: &str) -> Result<String> {
+        self.render_named("<template>", template)
+    }
+
+    /// Render a template, using `name` (usually its path) in error messages
+    pub fn render_named(&self, name: &str, template: &str) -> Result<String> {
+        let nodes = parse_template(&self.templates, name, template, &mut vec![name.to_string()])
+            .map_err(|e| BootsError::Template(format!("{}: {}", name, e)))?;
+
+        let mut out = Output {
+            text: String::with_capacity(template.len()),
+            unresolved: Vec::new(),
+        };
+        let mut scopes = Vec::new();
+        self.render_nodes(&nodes, &mut scopes, &mut out);
+
+        if self.strict && !out.unresolved.is_empty() {
+            let list: Vec<String> = out
+                .unresolved
+                .iter()
+                .map(|(source, line, raw)| format!("  {}:{}: {}", source, line, raw))
+                .collect();
+            return Err(BootsError::Template(format!(
+                "unresolved placeholders:\n{}",
+                list.join("\n")
+            )));
+        }
+        Ok(out.text)
+    }
+
+    fn render_nodes<'a>(
+        &'a self,
+        nodes: &'a [Node],
+        scopes: &mut Vec<Scope<'a>>,
+        out: &mut Output,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.text.push_str(text),
+                Node::Var {
+                    path,
+                    filters,
+                    raw,
+                    source,
+                    line,
+                } => match self.lookup(path, scopes) {
+                    Some(value) => {
+                        let text = filters
+                            .iter()
+                            .fold(value.to_text(), |text, filter| filter.apply(&text));
+                        out.text.push_str(&text);
+                    }
+                    None => {
+                        out.text.push_str(raw);
+                        let entry = (source.clone(), *line, raw.clone());
+                        if !out.unresolved.contains(&entry) {
+                            out.unresolved.push(entry);
+                        }
+                    }
+                },
+                Node::Partial(nodes) => self.render_nodes(nodes, scopes, out),
+                Node::If {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if self.evaluate(condition, scopes) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.render_nodes(branch, scopes, out);
+                }
+                Node::Each {
+                    path,
+                    body,
+                    otherwise,
+                } => {
+                    let items = match self.lookup(path, scopes) {
+                        Some(Cow::Borrowed(Value::List(items))) if !items.is_empty() => items,
+                        _ => {
+                            self.render_nodes(otherwise, scopes, out);
+                            continue;
+                        }
+                    };
+                    let len = items.len();
+                    for (index, item) in items.iter().enumerate() {
+                        scopes.push(Scope { item, index, len });
+                        self.render_nodes(body, scopes, out);
+                        scopes.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn lookup<'a>(&'a self, path: &str, scopes: &[Scope<'a>]) -> Option<Cow<'a, Value>> {
+        let segments: Vec<&str> = path.split('.').collect();
+        let (head, rest) = segments.split_first()?;
+
+        match *head {
+            "this" => return scopes.last()?.item.get_path(rest).map(Cow::Borrowed),
+            "@index" => {
+                let index = scopes.last()?.index;
+                return Some(Cow::Owned(Value::String(index.to_string())));
+            }
+            "@first" => return Some(Cow::Owned(Value::Bool(scopes.last()?.index == 0))),
+            "@last" => {
+                let scope = scopes.last()?;
+                return Some(Cow::Owned(Value::Bool(scope.index + 1 == scope.len)));
+            }
+            _ => {}
+        }
+
+        // Fields of map items shadow globals, innermost loop first
+        for scope in scopes.iter().rev() {
+            if let Value::Map(map) = scope.item
+                && let Some(value) = map.get(*head)
+            {
+                return value.get_path(rest).map(Cow::Borrowed);
+            }
+        }
+
+        self.variables.get(*head)?.get_path(rest).map(Cow::Borrowed)
+    }
+
+    fn evaluate<'a>(&'a self, condition: &'a Condition, scopes: &[Scope<'a>]) -> bool {
+        condition
+            .iter()
+            .any(|all| all.iter().all(|test| self.evaluate_test(test, scopes)))
+    }
+
+    fn evaluate_test<'a>(&'a self, test: &'a Test, scopes: &[Scope<'a>]) -> bool {
+        let result = match &test.comparison {
+            None => self
+                .resolve(&test.left, scopes)
+                .is_some_and(|v| v.is_truthy()),
+            Some((op, right)) => {
+                let left = self.resolve(&test.left, scopes).map(|v| v.to_text());
+                let right = self.resolve(right, scopes).map(|v| v.to_text());
+                match op {
+                    CmpOp::Eq => left == right,
+                    CmpOp::Ne => left != right,
+                }
+            }
+        };
+        result != test.negate
+    }
+
+    fn resolve<'a>(&'a self, operand: &'a Operand, scopes: &[Scope<'a>]) -> Option<Cow<'a, Value>> {
+        match operand {
+            Operand::Path(path) => self.lookup(path, scopes),
+            Operand::Literal(value) => Some(Cow::Borrowed(value)),
         }
-        result
     }
 }
 
@@ -31,6 +303,461 @@ impl Default for TemplateEngine {
     }
 }
 
+struct Output {
+    text: String,
+    /// `(template, line, placeholder)` for every variable without a value
+    unresolved: Vec<(Rc<str>, usize, String)>,
+}
+
+struct Scope<'a> {
+    item: &'a Value,
+    index: usize,
+    len: usize,
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Var {
+        path: String,
+        filters: Vec<Filter>,
+        raw: String,
+        line: usize,
+    },
+    Partial {
+        path: String,
+        line: usize,
+    },
+    OpenIf(Condition),
+    OpenEach(String),
+    Else,
+    Close(String),
+    /// A well-formed tag that cannot be rendered, e.g. an unknown filter
+    Invalid(String),
+}
+
+impl Token {
+    fn is_block(&self) -> bool {
+        matches!(
+            self,
+            Token::Partial { .. }
+                | Token::OpenIf(_)
+                | Token::OpenEach(_)
+                | Token::Else
+                | Token::Close(_)
+        )
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var {
+        path: String,
+        filters: Vec<Filter>,
+        raw: String,
+        source: Rc<str>,
+        line: usize,
+    },
+    /// An included template, already parsed
+    Partial(Vec<Node>),
+    If {
+        condition: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        path: String,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// Tests joined by `||` of `&&` groups; `&&` binds tighter, there are no parentheses
+type Condition = Vec<Vec<Test>>;
+
+#[derive(Debug)]
+struct Test {
+    negate: bool,
+    left: Operand,
+    comparison: Option<(CmpOp, Operand)>,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Path(String),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CmpOp {
+    Eq,
+    Ne,
+}
+
+fn tokenize(template: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    let mut line = 1;
+
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+
+        // `\{{` is an escaped literal `{{`; drop the backslash and skip to the next `{{`
+        if rest[..start].ends_with('\\') {
+            text.push_str(&rest[..start - 1]);
+            text.push_str("{{");
+            line += rest[..start].matches('\n').count();
+            rest = after_open;
+            continue;
+        }
+
+        let tag = after_open
+            .find("}}")
+            .and_then(|end| parse_tag(&after_open[..end]).map(|tag| (tag, end)));
+
+        match tag {
+            Some((tag, end)) => {
+                text.push_str(&rest[..start]);
+                line += rest[..start].matches('\n').count();
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                let raw_len = start + 2 + end + 2;
+                tokens.push(match tag {
+                    Token::Var { path, filters, .. } => Token::Var {
+                        path,
+                        filters,
+                        raw: rest[start..raw_len].to_string(),
+                        line,
+                    },
+                    Token::Partial { path, .. } => Token::Partial { path, line },
+                    Token::Invalid(message) => {
+                        Token::Invalid(format!("line {}: {}", line, message))
+                    }
+                    other => other,
+                });
+                line += rest[start..raw_len].matches('\n').count();
+                rest = &rest[raw_len..];
+            }
+            None => {
+                // Not a tag: keep the braces as literal text and keep scanning
+                text.push_str(&rest[..start + 2]);
+                line += rest[..start].matches('\n').count();
+                rest = after_open;
+            }
+        }
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+fn parse_tag(inner: &str) -> Option<Token> {
+    let inner = inner.trim();
+
+    if let Some(block) = inner.strip_prefix('#') {
+        let (keyword, arg) = block.split_once(char::is_whitespace)?;
+        let arg = arg.trim();
+        return match keyword {
+            "if" => parse_condition(arg).map(Token::OpenIf),
+            "each" if is_path(arg) => Some(Token::OpenEach(arg.to_string())),
+            _ => None,
+        };
+    }
+
+    if let Some(keyword) = inner.strip_prefix('/') {
+        return matches!(keyword.trim(), "if" | "each")
+            .then(|| Token::Close(keyword.trim().to_string()));
+    }
+
+    if let Some(path) = inner.strip_prefix('>') {
+        let path = path.trim();
+        let valid = !path.is_empty()
+            && path
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+        return valid.then(|| Token::Partial {
+            path: path.to_string(),
+            line: 0,
+        });
+    }
+
+    if inner == "else" {
+        return Some(Token::Else);
+    }
+
+    let mut parts = inner.split('|').map(str::trim);
+    let path = parts.next().filter(|p| is_path(p))?;
+    let mut filters = Vec::new();
+    for name in parts {
+        if !is_path(name) || name.contains('.') {
+            return None;
+        }
+        match Filter::from_name(name) {
+            Some(filter) => filters.push(filter),
+            None => return Some(Token::Invalid(format!("unknown filter `{}`", name))),
+        }
+    }
+
+    Some(Token::Var {
+        path: path.to_string(),
+        filters,
+        raw: String::new(),
+        line: 0,
+    })
+}
+
+fn parse_condition(expr: &str) -> Option<Condition> {
+    expr.split("||")
+        .map(|any| {
+            any.split("&&")
+                .map(|test| parse_test(test.trim()))
+                .collect()
+        })
+        .collect()
+}
+
+fn parse_test(expr: &str) -> Option<Test> {
+    let (negate, expr) = match expr.strip_prefix('!') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, expr),
+    };
+
+    for (symbol, op) in [("==", CmpOp::Eq), ("!=", CmpOp::Ne)] {
+        if let Some((left, right)) = expr.split_once(symbol) {
+            return Some(Test {
+                negate,
+                left: parse_operand(left.trim())?,
+                comparison: Some((op, parse_operand(right.trim())?)),
+            });
+        }
+    }
+
+    Some(Test {
+        negate,
+        left: parse_operand(expr.trim())?,
+        comparison: None,
+    })
+}
+
+fn parse_operand(s: &str) -> Option<Operand> {
+    if let Some(literal) = s.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        return Some(Operand::Literal(Value::String(literal.to_string())));
+    }
+    match s {
+        "true" => Some(Operand::Literal(Value::Bool(true))),
+        "false" => Some(Operand::Literal(Value::Bool(false))),
+        _ if is_path(s) => Some(Operand::Path(s.to_string())),
+        _ => None,
+    }
+}
+
+/// A variable path: `name`, `this`, `this.field`, `@index`, `item.field`
+fn is_path(s: &str) -> bool {
+    let s = s.strip_prefix('@').unwrap_or(s);
+    !s.is_empty()
+        && s.split('.').all(|segment| {
+            let mut chars = segment.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Block tags that sit alone on a line remove that whole line from the output,
+/// so `{{#if}}`/`{{/if}}` can wrap sections without leaving blank lines behind.
+fn strip_standalone(mut tokens: Vec<Token>) -> Vec<Token> {
+    let standalone: Vec<bool> = (0..tokens.len())
+        .map(|i| {
+            if !tokens[i].is_block() {
+                return false;
+            }
+            let before_ok = match i.checked_sub(1) {
+                None => true,
+                Some(j) => matches!(&tokens[j], Token::Text(text)
+                    if line_tail(text).is_some() && (j == 0 || text.contains('\n'))),
+            };
+            let after_ok = match tokens.get(i + 1) {
+                None => true,
+                Some(Token::Text(text)) => line_head(text).is_some(),
+                Some(_) => false,
+            };
+            before_ok && after_ok
+        })
+        .collect();
+
+    for (i, _) in standalone.iter().enumerate().filter(|(_, s)| **s) {
+        if let Some(Token::Text(text)) = i.checked_sub(1).map(|j| &mut tokens[j]) {
+            let keep = line_tail(text).unwrap_or(text.len());
+            text.truncate(keep);
+        }
+        if let Some(Token::Text(text)) = tokens.get_mut(i + 1) {
+            let skip = line_head(text).unwrap_or(0);
+            text.drain(..skip);
+        }
+    }
+
+    tokens
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Text(text) if text.is_empty()))
+        .collect()
+}
+
+/// If `text` ends with a line containing only whitespace, the byte offset where that line starts.
+fn line_tail(text: &str) -> Option<usize> {
+    let start = text.rfind('\n').map_or(0, |i| i + 1);
+    text[start..]
+        .chars()
+        .all(|c| c == ' ' || c == '\t')
+        .then_some(start)
+}
+
+/// If `text` starts with whitespace up to a newline (or the end), the byte length to drop.
+fn line_head(text: &str) -> Option<usize> {
+    let trimmed = text.trim_start_matches([' ', '\t']);
+    let ws = text.len() - trimmed.len();
+    if trimmed.is_empty() {
+        Some(ws)
+    } else if trimmed.starts_with("\r\n") {
+        Some(ws + 2)
+    } else if trimmed.starts_with('\n') {
+        Some(ws + 1)
+    } else {
+        None
+    }
+}
+
+/// Parse errors are plain messages; `render_named` prefixes them with the template name
+type ParseResult<T> = std::result::Result<T, String>;
+
+/// Tokenize and parse a template, expanding partials. `stack` holds the templates
+/// currently being expanded, outermost first, for cycle detection.
+fn parse_template(
+    templates: &TemplateSource,
+    name: &str,
+    template: &str,
+    stack: &mut Vec<String>,
+) -> ParseResult<Vec<Node>> {
+    let tokens = strip_standalone(tokenize(template));
+    Parser {
+        templates,
+        tokens: tokens.into_iter(),
+        source: Rc::from(name),
+        stack,
+    }
+    .parse()
+}
+
+struct Parser<'s> {
+    templates: &'s TemplateSource,
+    tokens: std::vec::IntoIter<Token>,
+    source: Rc<str>,
+    stack: &'s mut Vec<String>,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> ParseResult<Vec<Node>> {
+        let (nodes, end) = self.parse_until()?;
+        match end {
+            End::Eof => Ok(nodes),
+            End::Else => Err("unexpected {{else}}".to_string()),
+            End::Close(name) => Err(format!("unexpected {{{{/{}}}}}", name)),
+        }
+    }
+
+    fn parse_until(&mut self) -> ParseResult<(Vec<Node>, End)> {
+        let mut nodes = Vec::new();
+        while let Some(token) = self.tokens.next() {
+            match token {
+                Token::Text(text) => nodes.push(Node::Text(text)),
+                Token::Var {
+                    path,
+                    filters,
+                    raw,
+                    line,
+                } => nodes.push(Node::Var {
+                    path,
+                    filters,
+                    raw,
+                    source: self.source.clone(),
+                    line,
+                }),
+                Token::Partial { path, line } => {
+                    nodes.push(Node::Partial(self.parse_partial(&path, line)?));
+                }
+                Token::Invalid(message) => return Err(message),
+                Token::OpenIf(condition) => {
+                    let (then, otherwise) = self.parse_block("if")?;
+                    nodes.push(Node::If {
+                        condition,
+                        then,
+                        otherwise,
+                    });
+                }
+                Token::OpenEach(path) => {
+                    let (body, otherwise) = self.parse_block("each")?;
+                    nodes.push(Node::Each {
+                        path,
+                        body,
+                        otherwise,
+                    });
+                }
+                Token::Else => return Ok((nodes, End::Else)),
+                Token::Close(name) => return Ok((nodes, End::Close(name))),
+            }
+        }
+        Ok((nodes, End::Eof))
+    }
+
+    fn parse_partial(&mut self, path: &str, line: usize) -> ParseResult<Vec<Node>> {
+        if self.stack.iter().any(|p| p == path) {
+            return Err(format!(
+                "line {}: partial cycle: {} -> {}",
+                line,
+                self.stack.join(" -> "),
+                path
+            ));
+        }
+
+        let template = self
+            .templates
+            .get(path)
+            .ok_or_else(|| format!("line {}: partial `{}` not found", line, path))?;
+
+        self.stack.push(path.to_string());
+        let nodes = parse_template(self.templates, path, &template, self.stack)
+            .map_err(|e| format!("line {}: {}: {}", line, path, e));
+        self.stack.pop();
+        nodes
+    }
+
+    fn parse_block(&mut self, name: &str) -> ParseResult<(Vec<Node>, Vec<Node>)> {
+        let (body, end) = self.parse_until()?;
+        let (otherwise, end) = match end {
+            End::Else => self.parse_until()?,
+            other => (Vec::new(), other),
+        };
+        match end {
+            End::Close(closed) if closed == name => Ok((body, otherwise)),
+            End::Close(closed) => Err(format!("{{{{#{}}}}} closed by {{{{/{}}}}}", name, closed)),
+            End::Else => Err(format!("duplicate {{{{else}}}} in {{{{#{}}}}}", name)),
+            End::Eof => Err(format!("unclosed {{{{#{}}}}}", name)),
+        }
+    }
+}
+
+enum End {
+    Eof,
+    Else,
+    Close(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,7 +767,7 @@ mod tests {
         let mut engine = TemplateEngine::new();
         engine.set("name", "test-project");
 
-        let result = engine.render("Project: {{name}}");
+        let result = engine.render("Project: {{name}}").unwrap();
         assert_eq!(result, "Project: test-project");
     }
 
@@ -51,9 +778,223 @@ mod tests {
         engine.set("version", "0.1.0");
 
         let template = "name = \"{{name}}\"\nversion = \"{{version}}\"";
-        let result = engine.render(template);
+        let result = engine.render(template).unwrap();
 
         assert!(result.contains("name = \"my-app\""));
         assert!(result.contains("version = \"0.1.0\""));
     }
+
+    #[test]
+    fn test_render_if_else() {
+        let mut engine = TemplateEngine::new();
+        engine.set("has_grpc", true);
+        engine.set("has_http", false);
+
+        let template = "{{#if has_grpc}}grpc{{/if}} {{#if has_http}}http{{else}}no-http{{/if}}";
+        assert_eq!(engine.render(template).unwrap(), "grpc no-http");
+    }
+
+    #[test]
+    fn test_render_if_comparison() {
+        let mut engine = TemplateEngine::new();
+        engine.set("persistence", "sqlite");
+
+        let template = "{{#if persistence == \"postgres\"}}pg{{/if}}\
+            {{#if persistence != \"postgres\"}}other{{/if}}\
+            {{#if !missing}}!{{/if}}";
+        assert_eq!(engine.render(template).unwrap(), "other!");
+    }
+
+    #[test]
+    fn test_eval_and_or() {
+        let mut engine = TemplateEngine::new();
+        engine.set("has_grpc", true);
+        engine.set("persistence", "sqlite");
+
+        assert!(
+            engine
+                .eval("has_grpc && persistence == \"sqlite\"")
+                .unwrap()
+        );
+        assert!(!engine.eval("has_grpc && !persistence").unwrap());
+        assert!(
+            engine
+                .eval("missing || has_grpc && persistence != \"postgres\"")
+                .unwrap()
+        );
+        assert!(!engine.eval("missing || !has_grpc").unwrap());
+        assert!(engine.eval("has_grpc &&").is_err());
+    }
+
+    #[test]
+    fn test_render_each() {
+        let mut engine = TemplateEngine::new();
+        engine.set("modules", vec!["core", "api"]);
+
+        let template = "{{#each modules}}{{@index}}:{{this}}{{#if @last}}{{else}},{{/if}}{{/each}}";
+        assert_eq!(engine.render(template).unwrap(), "0:core,1:api");
+    }
+
+    #[test]
+    fn test_render_each_map_items() {
+        let mut engine = TemplateEngine::new();
+        let item: BTreeMap<String, Value> =
+            BTreeMap::from([("name".to_string(), Value::from("core"))]);
+        engine.set("project", "demo");
+        engine.set("crates", vec![Value::Map(item)]);
+
+        let template = "{{#each crates}}{{project}}-{{name}} {{this.name}}{{/each}}";
+        assert_eq!(engine.render(template).unwrap(), "demo-core core");
+    }
+
+    #[test]
+    fn test_render_each_empty_uses_else() {
+        let mut engine = TemplateEngine::new();
+        engine.set("items", Vec::<String>::new());
+
+        let template = "{{#each items}}{{this}}{{else}}none{{/each}}";
+        assert_eq!(engine.render(template).unwrap(), "none");
+    }
+
+    #[test]
+    fn test_standalone_block_lines_are_removed() {
+        let mut engine = TemplateEngine::new();
+        engine.set("has_grpc", true);
+        engine.set("modules", vec!["core"]);
+
+        let template = "[deps]\n{{#if has_grpc}}\ntonic = \"0.11\"\n{{/if}}\nmembers = [\n{{#each modules}}\n    \"{{this}}\",\n{{/each}}\n]\n";
+        assert_eq!(
+            engine.render(template).unwrap(),
+            "[deps]\ntonic = \"0.11\"\nmembers = [\n    \"core\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_non_tag_braces_are_literal() {
+        let mut engine = TemplateEngine::new();
+        engine.set("name", "app");
+
+        let template = "<div style={{ padding: '2rem' }}>{{name}}</div> {{unknown}}";
+        assert_eq!(
+            engine.render(template).unwrap(),
+            "<div style={{ padding: '2rem' }}>app</div> {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_strict_reports_unresolved_placeholders() {
+        let mut engine = TemplateEngine::new();
+        engine.set("name", "app").strict(true);
+
+        let err = engine
+            .render_named(
+                "base/README.md",
+                "# {{name}}\n\n{{missing}} {{#if name}}{{other}}{{/if}}",
+            )
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("base/README.md:3: {{missing}}"), "{}", err);
+        assert!(err.contains("base/README.md:3: {{other}}"), "{}", err);
+        assert!(!err.contains("{{name}}"), "{}", err);
+    }
+
+    #[test]
+    fn test_strict_ignores_non_tag_braces() {
+        let mut engine = TemplateEngine::new();
+        engine.strict(true);
+
+        let template = "style={{ color: 'red' }} format!(\"{{{}}}\")";
+        assert_eq!(engine.render(template).unwrap(), template);
+    }
+
+    #[test]
+    fn test_render_filters() {
+        let mut engine = TemplateEngine::new();
+        engine.set("name", "user-service");
+
+        let template = "{{name | snake}} {{ name | pascal }} {{name|camel}} {{name | shouty_snake}} {{name | title | kebab}}";
+        assert_eq!(
+            engine.render(template).unwrap(),
+            "user_service UserService userService USER_SERVICE user-service"
+        );
+    }
+
+    #[test]
+    fn test_unknown_filter_error() {
+        let engine = TemplateEngine::new();
+        let err = engine
+            .render_named("proto/service.proto", "\n{{name | shout}}")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Template error: proto/service.proto: line 2: unknown filter `shout`"
+        );
+    }
+
+    #[test]
+    fn test_values_are_not_rendered_again() {
+        let mut engine = TemplateEngine::new();
+        engine.set("description", "uses {{name}} and {{#if x}}");
+        engine.set("name", "app");
+
+        let result = engine.render("{{description}} / {{name}}").unwrap();
+        assert_eq!(result, "uses {{name}} and {{#if x}} / app");
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let mut engine = TemplateEngine::new();
+        engine.set("project_name", "app").strict(true);
+
+        let template = "key: $\\{{ runner.os }}-{{project_name}}\n\\{{project_name}}";
+        assert_eq!(
+            engine.render(template).unwrap(),
+            "key: ${{ runner.os }}-app\n{{project_name}}"
+        );
+        assert!(engine.render("${{ runner.os }}").is_err());
+    }
+
+    #[test]
+    fn test_render_partial() {
+        let engine = TemplateEngine::new();
+
+        let result = engine
+            .render("use axum::Json;\n\n{{> partials/api/health.rs}}\n// end\n")
+            .unwrap();
+        assert!(result.contains("pub async fn health()"));
+        assert!(result.ends_with("}\n// end\n"), "{}", result);
+    }
+
+    #[test]
+    fn test_missing_partial_error() {
+        let engine = TemplateEngine::new();
+        let err = engine.render("{{> partials/missing.rs}}").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("partial `partials/missing.rs` not found")
+        );
+    }
+
+    #[test]
+    fn test_partial_cycle_error() {
+        let engine = TemplateEngine::new();
+        let err = engine
+            .render_named("partials/api/health.rs", "{{> partials/api/health.rs}}")
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("partial cycle: partials/api/health.rs -> partials/api/health.rs"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_blocks_error() {
+        let engine = TemplateEngine::new();
+        assert!(engine.render("{{#if a}}x").is_err());
+        assert!(engine.render("x{{/if}}").is_err());
+        assert!(engine.render("{{#if a}}x{{/each}}").is_err());
+    }
 }
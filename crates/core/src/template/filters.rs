@@ -0,0 +1,159 @@
+/// A filter applied to a placeholder value, e.g. `{{project_name | pascal}}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Snake,
+    Pascal,
+    Camel,
+    Kebab,
+    ShoutySnake,
+    Title,
+    Lower,
+    Upper,
+}
+
+impl Filter {
+    pub const ALL: [Filter; 8] = [
+        Filter::Snake,
+        Filter::Pascal,
+        Filter::Camel,
+        Filter::Kebab,
+        Filter::ShoutySnake,
+        Filter::Title,
+        Filter::Lower,
+        Filter::Upper,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Filter::Snake => "snake",
+            Filter::Pascal => "pascal",
+            Filter::Camel => "camel",
+            Filter::Kebab => "kebab",
+            Filter::ShoutySnake => "shouty_snake",
+            Filter::Title => "title",
+            Filter::Lower => "lower",
+            Filter::Upper => "upper",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            Filter::Snake => to_snake_case(value),
+            Filter::Pascal => to_pascal_case(value),
+            Filter::Camel => to_camel_case(value),
+            Filter::Kebab => to_kebab_case(value),
+            Filter::ShoutySnake => to_shouty_snake_case(value),
+            Filter::Title => to_title_case(value),
+            Filter::Lower => value.to_lowercase(),
+            Filter::Upper => value.to_uppercase(),
+        }
+    }
+}
+
+/// `my-api` -> `my_api`
+pub fn to_snake_case(s: &str) -> String {
+    join_words(s, "_", str::to_lowercase)
+}
+
+/// `my-api` -> `MyApi`
+pub fn to_pascal_case(s: &str) -> String {
+    join_words(s, "", capitalize)
+}
+
+/// `my-api` -> `myApi`
+pub fn to_camel_case(s: &str) -> String {
+    let pascal = to_pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `MyApi` -> `my-api`
+pub fn to_kebab_case(s: &str) -> String {
+    join_words(s, "-", str::to_lowercase)
+}
+
+/// `my-api` -> `MY_API`
+pub fn to_shouty_snake_case(s: &str) -> String {
+    join_words(s, "_", str::to_uppercase)
+}
+
+/// `my-api` -> `My Api`
+pub fn to_title_case(s: &str) -> String {
+    join_words(s, " ", capitalize)
+}
+
+fn join_words(s: &str, separator: &str, transform: impl Fn(&str) -> String) -> String {
+    words(s)
+        .iter()
+        .map(|w| transform(w))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Split an identifier into words on `-`, `_`, `.`, whitespace and case boundaries
+/// (`myHTTPServer` -> `my`, `HTTP`, `Server`).
+fn words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in s.split(|c: char| c == '-' || c == '_' || c == '.' || c.is_whitespace()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (idx, c) = chars[i];
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next_is_lower));
+            if boundary {
+                words.push(&part[start..idx]);
+                start = idx;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversions() {
+        assert_eq!(to_snake_case("my-api"), "my_api");
+        assert_eq!(to_pascal_case("my-api"), "MyApi");
+        assert_eq!(to_camel_case("my-api"), "myApi");
+        assert_eq!(to_kebab_case("my_api"), "my-api");
+        assert_eq!(to_shouty_snake_case("my-api"), "MY_API");
+        assert_eq!(to_title_case("my-api"), "My Api");
+    }
+
+    #[test]
+    fn test_case_boundaries() {
+        assert_eq!(to_snake_case("myHTTPServer"), "my_http_server");
+        assert_eq!(to_kebab_case("UserService2"), "user-service2");
+        assert_eq!(to_pascal_case("user service"), "UserService");
+        assert_eq!(to_camel_case("--"), "");
+    }
+}
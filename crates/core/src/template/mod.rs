@@ -1,5 +1,11 @@
 pub mod embedded;
 pub mod engine;
+pub mod filters;
+pub mod pack;
+pub mod source;
 
 pub use embedded::Templates;
-pub use engine::TemplateEngine;
+pub use engine::{TemplateEngine, Value};
+pub use filters::Filter;
+pub use pack::{PackManifest, TemplatePack};
+pub use source::TemplateSource;
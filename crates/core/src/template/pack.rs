@@ -0,0 +1,439 @@
+use crate::config::{Module, ProjectType};
+use crate::error::{BootsError, Result};
+use flate2::read::GzDecoder;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml_edit::DocumentMut;
+
+const MANIFEST: &str = "pack.toml";
+
+/// Metadata from a pack's `pack.toml`:
+///
+/// ```toml
+/// [pack]
+/// name = "company"
+/// version = "1.2.0"
+/// min_boots_version = "0.2.0"
+/// project_types = ["service", "cli"]
+/// modules = ["api", "runtime"]
+/// hooks = ["npm install --prefix frontend"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackManifest {
+    pub name: String,
+    pub version: String,
+    pub min_boots_version: Option<String>,
+    /// Project types the pack applies to; empty means all
+    pub project_types: Vec<ProjectType>,
+    /// Modules whose templates (under `modules/<name>/`) the pack provides; empty means any
+    pub modules: Vec<Module>,
+    /// Shell commands to run in the generated project
+    pub hooks: Vec<String>,
+}
+
+/// A versioned set of templates mirroring the built-in `templates/` layout,
+/// loaded from a directory, a `.tar`/`.tar.gz` archive, or a git checkout at a ref.
+#[derive(Debug)]
+pub struct TemplatePack {
+    pub manifest: PackManifest,
+    /// Where the pack was loaded from, absolute when it exists
+    pub location: PathBuf,
+    /// The git ref it was read at, if any
+    pub git_ref: Option<String>,
+    files: PackFiles,
+}
+
+#[derive(Debug)]
+enum PackFiles {
+    Dir(PathBuf),
+    Archive(BTreeMap<String, Vec<u8>>),
+}
+
+impl TemplatePack {
+    /// Load a pack from `path`. With `git_ref`, `path` must be a git checkout and the
+    /// pack is read from that ref rather than the working tree.
+    pub fn load(path: &Path, git_ref: Option<&str>) -> Result<Self> {
+        let files = match git_ref {
+            Some(git_ref) => PackFiles::Archive(read_git_ref(path, git_ref)?),
+            None if path.is_dir() => PackFiles::Dir(path.to_path_buf()),
+            None if path.is_file() => PackFiles::Archive(read_archive(&fs::read(path)?)?),
+            None => {
+                return Err(BootsError::TemplatePack(format!(
+                    "not found: {}",
+                    path.display()
+                )));
+            }
+        };
+
+        let manifest = files
+            .read(MANIFEST)
+            .ok_or_else(|| {
+                BootsError::TemplatePack(format!("{} missing in {}", MANIFEST, path.display()))
+            })
+            .and_then(|text| parse_manifest(&String::from_utf8_lossy(&text)))?;
+
+        if let Some(min) = &manifest.min_boots_version
+            && !version_satisfies(env!("CARGO_PKG_VERSION"), min)?
+        {
+            return Err(BootsError::TemplatePack(format!(
+                "{} {} requires boots >= {}, this is {}",
+                manifest.name,
+                manifest.version,
+                min,
+                env!("CARGO_PKG_VERSION")
+            )));
+        }
+
+        let pack = Self {
+            manifest,
+            location: fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+            git_ref: git_ref.map(str::to_string),
+            files,
+        };
+        pack.check_modules()?;
+        Ok(pack)
+    }
+
+    /// Fail if the pack has templates under `modules/<name>/` for a module that does not
+    /// exist or that `pack.toml` does not declare
+    fn check_modules(&self) -> Result<()> {
+        let manifest = &self.manifest;
+        for path in self.list() {
+            let Some((name, _)) = path
+                .strip_prefix("modules/")
+                .and_then(|rest| rest.split_once('/'))
+            else {
+                continue;
+            };
+            let module: Module = name.parse().map_err(|_| {
+                BootsError::TemplatePack(format!(
+                    "{} has templates for unknown module '{}': {}",
+                    manifest.name, name, path
+                ))
+            })?;
+            if !manifest.modules.is_empty() && !manifest.modules.contains(&module) {
+                return Err(BootsError::TemplatePack(format!(
+                    "{} has templates for module '{}', which {} does not list in modules",
+                    manifest.name, module, MANIFEST
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail if the pack does not declare support for `project_type`
+    pub fn check_project_type(&self, project_type: ProjectType) -> Result<()> {
+        if self.manifest.project_types.is_empty()
+            || self.manifest.project_types.contains(&project_type)
+        {
+            Ok(())
+        } else {
+            Err(BootsError::TemplatePack(format!(
+                "{} does not support '{}' projects",
+                self.manifest.name, project_type
+            )))
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<String> {
+        if path == MANIFEST {
+            return None;
+        }
+        self.files
+            .read(path)
+            .map(|data| String::from_utf8_lossy(&data).to_string())
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        let mut paths = match &self.files {
+            PackFiles::Dir(dir) => {
+                let mut paths = BTreeSet::new();
+                super::source::collect_files(dir, dir, &mut paths);
+                paths.into_iter().collect()
+            }
+            PackFiles::Archive(files) => files.keys().cloned().collect::<Vec<_>>(),
+        };
+        paths.retain(|p| p != MANIFEST);
+        paths
+    }
+}
+
+impl PackFiles {
+    fn read(&self, path: &str) -> Option<Vec<u8>> {
+        match self {
+            PackFiles::Dir(dir) => fs::read(dir.join(path)).ok(),
+            PackFiles::Archive(files) => files.get(path).cloned(),
+        }
+    }
+}
+
+fn read_git_ref(repo: &Path, git_ref: &str) -> Result<BTreeMap<String, Vec<u8>>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["archive", "--format=tar", git_ref])
+        .output()
+        .map_err(|e| BootsError::TemplatePack(format!("failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BootsError::TemplatePack(format!(
+            "git archive {} in {} failed: {}",
+            git_ref,
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    read_archive(&output.stdout)
+}
+
+/// Read a tar (optionally gzipped) into memory. A single top-level directory
+/// wrapping `pack.toml` is stripped, as produced by `tar czf pack.tgz my-pack/`.
+fn read_archive(data: &[u8]) -> Result<BTreeMap<String, Vec<u8>>> {
+    let reader: Box<dyn Read + '_> = if data.starts_with(&[0x1f, 0x8b]) {
+        Box::new(GzDecoder::new(data))
+    } else {
+        Box::new(data)
+    };
+
+    let archive_error =
+        |e: std::io::Error| BootsError::TemplatePack(format!("invalid archive: {}", e));
+    let mut archive = tar::Archive::new(reader);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries().map_err(archive_error)? {
+        let mut entry = entry.map_err(archive_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(archive_error)?;
+        let path: Vec<String> = path
+            .components()
+            .filter_map(|c| match c {
+                std::path::Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(archive_error)?;
+        files.insert(path.join("/"), content);
+    }
+
+    if !files.contains_key(MANIFEST) {
+        let wrapper = files
+            .keys()
+            .find_map(|k| k.strip_suffix(&format!("/{}", MANIFEST)))
+            .filter(|prefix| !prefix.contains('/'))
+            .map(|prefix| format!("{}/", prefix));
+        if let Some(prefix) = wrapper {
+            files = files
+                .into_iter()
+                .filter_map(|(k, v)| k.strip_prefix(&prefix).map(|k| (k.to_string(), v)))
+                .collect();
+        }
+    }
+    Ok(files)
+}
+
+fn parse_manifest(text: &str) -> Result<PackManifest> {
+    let invalid = |msg: String| BootsError::TemplatePack(format!("invalid {}: {}", MANIFEST, msg));
+    let doc: DocumentMut = text.parse().map_err(|e| invalid(format!("{}", e)))?;
+    let pack = doc
+        .get("pack")
+        .and_then(|p| p.as_table_like())
+        .ok_or_else(|| invalid("missing [pack] table".to_string()))?;
+
+    let string = |key: &str| pack.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let list = |key: &str| -> Vec<String> {
+        pack.get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    Ok(PackManifest {
+        name: string("name").ok_or_else(|| invalid("missing name".to_string()))?,
+        version: string("version").ok_or_else(|| invalid("missing version".to_string()))?,
+        min_boots_version: string("min_boots_version"),
+        project_types: list("project_types")
+            .iter()
+            .map(|t| t.parse())
+            .collect::<Result<_>>()?,
+        modules: list("modules")
+            .iter()
+            .map(|m| m.parse())
+            .collect::<Result<_>>()?,
+        hooks: list("hooks"),
+    })
+}
+
+/// `actual >= required`, comparing `major.minor.patch` numerically
+fn version_satisfies(actual: &str, required: &str) -> Result<bool> {
+    fn parse(v: &str) -> Option<(u64, u64, u64)> {
+        let core = v.trim().trim_start_matches('v').split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|p| p.parse::<u64>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        Some((major, minor, patch))
+    }
+    let required_version = parse(required)
+        .ok_or_else(|| BootsError::TemplatePack(format!("invalid version: {}", required)))?;
+    Ok(parse(actual).is_some_and(|actual| actual >= required_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MANIFEST_TOML: &str = r#"[pack]
+name = "company"
+version = "1.2.0"
+min_boots_version = "0.1.0"
+project_types = ["service"]
+modules = ["api"]
+hooks = ["make setup"]
+"#;
+
+    fn write_pack(dir: &Path) {
+        fs::create_dir_all(dir.join("docker")).unwrap();
+        fs::write(dir.join(MANIFEST), MANIFEST_TOML).unwrap();
+        fs::write(dir.join("docker/Dockerfile"), "FROM company/rust").unwrap();
+    }
+
+    fn tar_gz(dir: &Path, prefix: &str) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all(prefix, dir).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_load_dir_pack() {
+        let dir = TempDir::new().unwrap();
+        write_pack(dir.path());
+
+        let pack = TemplatePack::load(dir.path(), None).unwrap();
+        assert_eq!(pack.manifest.name, "company");
+        assert_eq!(pack.manifest.project_types, vec![ProjectType::Service]);
+        assert_eq!(pack.manifest.modules, vec![Module::Api]);
+        assert_eq!(pack.manifest.hooks, vec!["make setup".to_string()]);
+        assert_eq!(pack.get("docker/Dockerfile").unwrap(), "FROM company/rust");
+        assert_eq!(pack.list(), vec!["docker/Dockerfile".to_string()]);
+        assert!(pack.check_project_type(ProjectType::Service).is_ok());
+        assert!(pack.check_project_type(ProjectType::Lib).is_err());
+    }
+
+    #[test]
+    fn test_pack_modules_cover_its_templates() {
+        let dir = TempDir::new().unwrap();
+        write_pack(dir.path());
+        fs::create_dir_all(dir.path().join("modules/api/src")).unwrap();
+        fs::write(dir.path().join("modules/api/src/lib.rs"), "// api").unwrap();
+        assert!(TemplatePack::load(dir.path(), None).is_ok());
+
+        fs::create_dir_all(dir.path().join("modules/cli/src")).unwrap();
+        fs::write(dir.path().join("modules/cli/src/main.rs"), "// cli").unwrap();
+        let err = TemplatePack::load(dir.path(), None).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("templates for module 'cli', which pack.toml does not list"),
+            "{}",
+            err
+        );
+
+        // Without a modules list, any module may be overridden but not an unknown one
+        fs::write(
+            dir.path().join(MANIFEST),
+            "[pack]\nname = \"company\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        assert!(TemplatePack::load(dir.path(), None).is_ok());
+        fs::create_dir_all(dir.path().join("modules/web")).unwrap();
+        fs::write(dir.path().join("modules/web/index.html"), "").unwrap();
+        let err = TemplatePack::load(dir.path(), None).unwrap_err();
+        assert!(err.to_string().contains("unknown module 'web'"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_tarball_pack() {
+        let src = TempDir::new().unwrap();
+        write_pack(src.path());
+        let out = TempDir::new().unwrap();
+        let tarball = out.path().join("pack.tar.gz");
+        fs::write(&tarball, tar_gz(src.path(), "company-templates")).unwrap();
+
+        let pack = TemplatePack::load(&tarball, None).unwrap();
+        assert_eq!(pack.manifest.version, "1.2.0");
+        assert_eq!(pack.get("docker/Dockerfile").unwrap(), "FROM company/rust");
+    }
+
+    #[test]
+    fn test_load_git_ref_pack() {
+        let repo = TempDir::new().unwrap();
+        write_pack(repo.path());
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=boots",
+                    "-c",
+                    "user.email=boots@example.com",
+                ])
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "v1"]);
+        git(&["tag", "v1"]);
+        fs::write(repo.path().join("docker/Dockerfile"), "FROM changed").unwrap();
+
+        let pack = TemplatePack::load(repo.path(), Some("v1")).unwrap();
+        assert_eq!(pack.get("docker/Dockerfile").unwrap(), "FROM company/rust");
+        assert!(TemplatePack::load(repo.path(), Some("missing-ref")).is_err());
+    }
+
+    #[test]
+    fn test_pack_requires_newer_boots() {
+        let dir = TempDir::new().unwrap();
+        write_pack(dir.path());
+        fs::write(
+            dir.path().join(MANIFEST),
+            MANIFEST_TOML.replace("0.1.0", "99.0.0"),
+        )
+        .unwrap();
+
+        let err = TemplatePack::load(dir.path(), None).unwrap_err();
+        assert!(
+            err.to_string().contains("requires boots >= 99.0.0"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_missing_manifest() {
+        let dir = TempDir::new().unwrap();
+        assert!(TemplatePack::load(dir.path(), None).is_err());
+    }
+
+    #[test]
+    fn test_version_satisfies() {
+        assert!(version_satisfies("0.2.0", "0.2").unwrap());
+        assert!(version_satisfies("0.10.0", "0.9.1").unwrap());
+        assert!(!version_satisfies("0.2.0", "0.2.1").unwrap());
+        assert!(version_satisfies("1.0.0", "not-a-version").is_err());
+    }
+}
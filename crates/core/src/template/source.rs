@@ -0,0 +1,205 @@
+use super::embedded::Templates;
+use super::pack::TemplatePack;
+use crate::config::ProjectType;
+use crate::error::{BootsError, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Where templates are loaded from: local directories and template packs layered over
+/// the embedded `templates/` tree. Lookups go file by file, most recently added layer first,
+/// so an override directory only needs the files it replaces.
+#[derive(Debug, Clone)]
+pub struct TemplateSource {
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Clone)]
+enum Layer {
+    Dir(PathBuf),
+    Pack(Arc<TemplatePack>),
+    Embedded,
+}
+
+impl TemplateSource {
+    /// Only the templates compiled into boots
+    pub fn embedded() -> Self {
+        Self {
+            layers: vec![Layer::Embedded],
+        }
+    }
+
+    /// Layer a local directory over the current source
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(BootsError::Template(format!(
+                "template directory not found: {}",
+                dir.display()
+            )));
+        }
+        self.layers.insert(0, Layer::Dir(dir));
+        Ok(self)
+    }
+
+    /// Layer a template pack over the current source
+    pub fn with_pack(mut self, pack: TemplatePack) -> Self {
+        self.layers.insert(0, Layer::Pack(Arc::new(pack)));
+        self
+    }
+
+    /// Every pack in this source, highest priority first
+    pub fn packs(&self) -> Vec<&TemplatePack> {
+        self.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::Pack(pack) => Some(pack.as_ref()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fail if any pack does not support `project_type`
+    pub fn check_project_type(&self, project_type: ProjectType) -> Result<()> {
+        self.layers.iter().try_for_each(|layer| match layer {
+            Layer::Pack(pack) => pack.check_project_type(project_type),
+            _ => Ok(()),
+        })
+    }
+
+    pub fn get(&self, path: &str) -> Option<String> {
+        if !is_relative_template_path(path) {
+            return None;
+        }
+        self.layers.iter().find_map(|layer| layer.get(path))
+    }
+
+    /// Every layer's copy of `path`, lowest priority (embedded) first
+    pub fn get_all(&self, path: &str) -> Vec<String> {
+        if !is_relative_template_path(path) {
+            return Vec::new();
+        }
+        self.layers
+            .iter()
+            .rev()
+            .filter_map(|layer| layer.get(path))
+            .collect()
+    }
+
+    /// All template paths under `prefix`, across every layer
+    pub fn list(&self, prefix: &str) -> Vec<String> {
+        let mut paths = BTreeSet::new();
+        for layer in &self.layers {
+            match layer {
+                Layer::Dir(dir) => collect_files(dir, dir, &mut paths),
+                Layer::Pack(pack) => paths.extend(pack.list()),
+                Layer::Embedded => paths.extend(Templates::list_templates("")),
+            }
+        }
+        paths
+            .into_iter()
+            .filter(|path| path.starts_with(prefix))
+            .collect()
+    }
+}
+
+impl Layer {
+    fn get(&self, path: &str) -> Option<String> {
+        match self {
+            Layer::Dir(dir) => fs::read(dir.join(path))
+                .ok()
+                .map(|data| String::from_utf8_lossy(&data).to_string()),
+            Layer::Pack(pack) => pack.get(path),
+            Layer::Embedded => Templates::get_template(path),
+        }
+    }
+}
+
+impl Default for TemplateSource {
+    fn default() -> Self {
+        Self::embedded()
+    }
+}
+
+/// Reject absolute paths and `..` so templates cannot escape an override directory
+pub(crate) fn is_relative_template_path(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+pub(super) fn collect_files(root: &Path, dir: &Path, paths: &mut BTreeSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, paths);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            paths.insert(relative.join("/"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_embedded_source() {
+        let source = TemplateSource::embedded();
+        assert!(source.get("docker/Dockerfile").is_some());
+        assert!(source.get("docker/missing").is_none());
+        assert!(
+            source
+                .list("github/")
+                .contains(&"github/test.yml".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dir_overrides_embedded_per_file() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("docker")).unwrap();
+        fs::write(dir.path().join("docker/Dockerfile"), "FROM company/rust").unwrap();
+        fs::write(dir.path().join("docker/extra"), "extra").unwrap();
+
+        let source = TemplateSource::embedded().with_dir(dir.path()).unwrap();
+        assert_eq!(
+            source.get("docker/Dockerfile").unwrap(),
+            "FROM company/rust"
+        );
+        assert_eq!(
+            source.get("docker/dockerignore"),
+            Templates::get_template("docker/dockerignore")
+        );
+
+        let listed = source.list("docker/");
+        assert!(listed.contains(&"docker/extra".to_string()));
+        assert!(listed.contains(&"docker/dockerignore".to_string()));
+    }
+
+    #[test]
+    fn test_rejects_escaping_paths() {
+        let dir = TempDir::new().unwrap();
+        let source = TemplateSource::embedded().with_dir(dir.path()).unwrap();
+        assert!(source.get("../etc/passwd").is_none());
+        assert!(source.get("/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn test_missing_dir_error() {
+        assert!(
+            TemplateSource::embedded()
+                .with_dir("/nonexistent/boots")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("{{project_name | snake}}");
+}
+
+use proto::{{project_name | snake}}_service_server::{{project_name | pascal}}Service;
+use proto::{{project_name | snake}}_service_server::{{project_name | pascal}}ServiceServer;
+use proto::{HealthCheckRequest, HealthCheckResponse};
+
+#[derive(Debug, Default)]
+pub struct Service;
+
+#[tonic::async_trait]
+impl {{project_name | pascal}}Service for Service {
+    async fn health_check(
+        &self,
+        _request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        Ok(Response::new(HealthCheckResponse {
+            healthy: true,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }))
+    }
+}
+
+pub fn create_grpc_service() -> {{project_name | pascal}}ServiceServer<Service> {
+    {{project_name | pascal}}ServiceServer::new(Service)
+}
@@ -1,4 +1,14 @@
+{{#if has_http}}
 pub mod handlers;
 pub mod routes;
+{{/if}}
+{{#if has_grpc}}
+pub mod grpc;
+{{/if}}
 
+{{#if has_http}}
 pub use routes::create_router;
+{{/if}}
+{{#if has_grpc}}
+pub use grpc::create_grpc_service;
+{{/if}}
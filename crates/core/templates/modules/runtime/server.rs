@@ -1,15 +1,26 @@
+{{#if has_http}}
 use {{project_name_snake}}_api::create_router;
+{{else}}
+use {{project_name_snake}}_api::create_grpc_service;
+{{/if}}
 use std::net::SocketAddr;
 use tracing::info;
 
 pub async fn run(port: u16) -> anyhow::Result<()> {
-    let app = create_router();
-
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
+{{#if has_http}}
+    let app = create_router();
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     info!("Server running on http://{}", addr);
     axum::serve(listener, app).await?;
+{{else}}
+    info!("gRPC server running on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(create_grpc_service())
+        .serve(addr)
+        .await?;
+{{/if}}
 
     Ok(())
 }
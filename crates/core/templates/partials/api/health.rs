@@ -0,0 +1,7 @@
+pub async fn health() -> Json<Value> {
+    Json(json!({ "healthy": true }))
+}
+
+pub async fn metrics() -> &'static str {
+    "# HELP up Server is up\nup 1"
+}
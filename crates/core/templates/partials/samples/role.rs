@@ -0,0 +1,21 @@
+/// User role for RBAC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Full access - can read, write, and manage
+    Admin,
+    /// Can read and write
+    Writer,
+    /// Read-only access
+    Reader,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Admin => write!(f, "admin"),
+            Role::Writer => write!(f, "writer"),
+            Role::Reader => write!(f, "reader"),
+        }
+    }
+}
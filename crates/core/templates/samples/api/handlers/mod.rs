@@ -5,16 +5,10 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 
 // Health & Metrics
-pub async fn health() -> impl IntoResponse {
-    Json(json!({ "healthy": true }))
-}
-
-pub async fn metrics() -> &'static str {
-    "# HELP up Server is up\nup 1"
-}
+{{> partials/api/health.rs}}
 
 // Posts
 #[derive(Debug, Serialize, Deserialize)]
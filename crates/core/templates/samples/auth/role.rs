@@ -1,15 +1,8 @@
 //! Role-based access control.
 
-/// User roles for authorization
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Role {
-    /// Full access - can read, write, and manage
-    Admin,
-    /// Can read and write
-    Writer,
-    /// Read-only access
-    Reader,
-}
+use serde::{Deserialize, Serialize};
+
+{{> partials/samples/role.rs}}
 
 impl Role {
     /// Check if role has admin privileges
@@ -28,16 +21,6 @@ impl Role {
     }
 }
 
-impl std::fmt::Display for Role {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Role::Admin => write!(f, "admin"),
-            Role::Writer => write!(f, "writer"),
-            Role::Reader => write!(f, "reader"),
-        }
-    }
-}
-
 impl std::str::FromStr for Role {
     type Err = String;
 
@@ -4,24 +4,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-/// User role for RBAC
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Role {
-    Admin,
-    Writer,
-    Reader,
-}
-
-impl std::fmt::Display for Role {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Role::Admin => write!(f, "admin"),
-            Role::Writer => write!(f, "writer"),
-            Role::Reader => write!(f, "reader"),
-        }
-    }
-}
+{{> partials/samples/role.rs}}
 
 /// User model
 #[derive(Debug, Clone, Serialize, Deserialize)]